@@ -0,0 +1,305 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, transfer_hook::TransferHook, BaseStateWithExtensions,
+    StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::CounterError;
+use crate::msg_codec::SwapParams;
+
+// Adapters implementing the `swap` interface below
+pub const GENERIC_ADAPTER_KIND: u8 = 0;
+
+// Most mints a route may walk through, bounds the compute one message can spend on hops
+pub const MAX_PATH_LEN: usize = 4;
+
+// Every DEX adapter exposes an Anchor-style `swap(amount_in: u64, min_amount_out: u64)`
+// instruction taking, in order: the authority (signer), the source and destination token
+// accounts, the token programs owning each of them, then any adapter-specific accounts.
+// Every hop of a route is handed the same adapter-specific accounts, the pools of all hops
+// together, so an adapter picks the pool of a hop by the mints of its source and destination.
+// Swapping a transfer hook mint, the hook's extra accounts are among the adapter-specific ones.
+const SWAP_IX_NAME: &[u8] = b"global:swap";
+
+pub struct SwapAccounts<'a, 'info> {
+    pub dex_program: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub source: &'a AccountInfo<'info>,
+    pub destination: &'a AccountInfo<'info>,
+    pub source_token_program: &'a AccountInfo<'info>,
+    pub destination_token_program: &'a AccountInfo<'info>,
+    pub adapter_accounts: &'a [AccountInfo<'info>],
+}
+
+pub fn swap(
+    accounts: SwapAccounts,
+    amount_in: u64,
+    min_amount_out: u64,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let mut data = hash(SWAP_IX_NAME).to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_amount_out.to_le_bytes());
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(*accounts.authority.key, true),
+        AccountMeta::new(*accounts.source.key, false),
+        AccountMeta::new(*accounts.destination.key, false),
+        AccountMeta::new_readonly(*accounts.source_token_program.key, false),
+        AccountMeta::new_readonly(*accounts.destination_token_program.key, false),
+    ];
+    metas.extend(accounts.adapter_accounts.iter().map(|account| {
+        if account.is_writable {
+            AccountMeta::new(*account.key, account.is_signer)
+        } else {
+            AccountMeta::new_readonly(*account.key, account.is_signer)
+        }
+    }));
+
+    let mut infos = vec![
+        accounts.authority.clone(),
+        accounts.source.clone(),
+        accounts.destination.clone(),
+        accounts.source_token_program.clone(),
+        accounts.destination_token_program.clone(),
+    ];
+    infos.extend_from_slice(accounts.adapter_accounts);
+    infos.push(accounts.dex_program.clone());
+
+    let ix = Instruction { program_id: *accounts.dex_program.key, accounts: metas, data };
+    invoke_signed(&ix, &infos, &[signer_seeds]).map_err(|err| {
+        msg!("Swap execution failed: {}", err);
+        error!(CounterError::SwapExecutionFailed)
+    })
+}
+
+/// Returns the mints a swap walks through, from `token_in` to `token_out`. An empty `path` is
+/// a single hop, otherwise `path` lists the whole route including both ends.
+pub fn route(swap_params: &SwapParams) -> Result<Vec<Pubkey>> {
+    let path = &swap_params.path;
+    if path.is_empty() {
+        require!(swap_params.token_in != swap_params.token_out, CounterError::InvalidSwapPath);
+        return Ok(vec![
+            Pubkey::new_from_array(swap_params.token_in),
+            Pubkey::new_from_array(swap_params.token_out),
+        ]);
+    }
+
+    require!(path.len() <= MAX_PATH_LEN, CounterError::PathTooLong);
+    require!(
+        path.len() >= 2
+            && path.first() == Some(&swap_params.token_in)
+            && path.last() == Some(&swap_params.token_out)
+            && path.windows(2).all(|hop| hop[0] != hop[1]),
+        CounterError::InvalidSwapPath
+    );
+    Ok(path.iter().map(|mint| Pubkey::new_from_array(*mint)).collect())
+}
+
+pub fn balance(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    Ok(TokenAccount::try_deserialize(&mut &data[..])?.amount)
+}
+
+/// Returns the fee a Token-2022 transfer-fee mint withholds when moving `amount` in `epoch`,
+/// zero for mints without the extension (including every classic SPL Token mint).
+pub fn transfer_fee(mint: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    if mint.owner != &anchor_spl::token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(CounterError::TransferFeeFailed)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+/// Decimals of `mint`, which has to be owned by `token_program`.
+pub fn mint_decimals(mint: &AccountInfo, token_program: &Pubkey) -> Result<u8> {
+    require!(mint.owner == token_program, CounterError::InvalidSwapAccount);
+    let data = mint.try_borrow_data()?;
+    let mint =
+        Mint::try_deserialize(&mut &data[..]).map_err(|_| CounterError::InvalidSwapAccount)?;
+    Ok(mint.decimals)
+}
+
+/// Whether `mint` has the Token-2022 transfer hook extension, set to a program or not yet.
+pub fn has_transfer_hook(mint: &AccountInfo) -> Result<bool> {
+    if mint.owner != &anchor_spl::token_2022::ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferHook>().is_ok())
+}
+
+pub struct TransferAccounts<'a, 'info> {
+    pub token_program: &'a AccountInfo<'info>,
+    pub from: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub to: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub hook_accounts: &'a [AccountInfo<'info>],
+}
+
+/// `transfer_checked` under either token program. If `mint` has a transfer hook, the accounts
+/// its validation account lists are looked up in `hook_accounts` and passed on to the hook.
+pub fn transfer_checked(
+    accounts: TransferAccounts,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        accounts.token_program.key,
+        accounts.from.clone(),
+        accounts.mint.clone(),
+        accounts.to.clone(),
+        accounts.authority.clone(),
+        accounts.hook_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_spl::token::spl_token;
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        transfer_fee::TransferFee, ExtensionType, StateWithExtensionsMut,
+    };
+
+    const DECIMALS: u8 = 6;
+    // Fee of the transfer-fee mints built here, 1% with no maximum
+    pub(crate) const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
+
+    fn spl_token_mint() -> Vec<u8> {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        let mint = spl_token::state::Mint {
+            decimals: DECIMALS,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_into_slice(&mut data);
+        data
+    }
+
+    pub(crate) fn token_2022_mint(extensions: &[ExtensionType]) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::TransferFeeConfig => {
+                    let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+                    let transfer_fee = TransferFee {
+                        epoch: 0.into(),
+                        maximum_fee: u64::MAX.into(),
+                        transfer_fee_basis_points: TRANSFER_FEE_BASIS_POINTS.into(),
+                    };
+                    config.older_transfer_fee = transfer_fee;
+                    config.newer_transfer_fee = transfer_fee;
+                }
+                ExtensionType::TransferHook => {
+                    state.init_extension::<TransferHook>(true).unwrap();
+                }
+                _ => panic!("test mints can't be built with {:?}", extension),
+            }
+        }
+        state.base = MintState { decimals: DECIMALS, is_initialized: true, ..Default::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn fee_of(mut data: Vec<u8>, owner: Pubkey, amount: u64) -> u64 {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        transfer_fee(&mint, amount, 0).unwrap()
+    }
+
+    #[test]
+    fn charges_no_fee_on_mints_without_the_extension() {
+        assert_eq!(fee_of(spl_token_mint(), anchor_spl::token::ID, 1_000), 0);
+        assert_eq!(fee_of(token_2022_mint(&[]), anchor_spl::token_2022::ID, 1_000), 0);
+    }
+
+    #[test]
+    fn charges_the_fee_of_transfer_fee_mints() {
+        let data = token_2022_mint(&[ExtensionType::TransferFeeConfig]);
+        assert_eq!(fee_of(data, anchor_spl::token_2022::ID, 1_000), 10);
+    }
+
+    #[test]
+    fn routes_an_empty_path_as_a_single_hop() {
+        let swap_params = crate::msg_codec::tests::swap_params();
+        let mints = [swap_params.token_in, swap_params.token_out].map(Pubkey::new_from_array);
+        assert_eq!(route(&swap_params).unwrap(), mints);
+    }
+
+    #[test]
+    fn rejects_a_single_hop_from_a_mint_to_itself() {
+        let mut swap_params = crate::msg_codec::tests::swap_params();
+        swap_params.token_out = swap_params.token_in;
+        assert_eq!(route(&swap_params).unwrap_err(), CounterError::InvalidSwapPath.into());
+    }
+
+    fn check_mint(mut data: Vec<u8>, owner: Pubkey, token_program: Pubkey) -> Result<u8> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mint = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        mint_decimals(&mint, &token_program)
+    }
+
+    #[test]
+    fn mint_decimals_reads_spl_token_mints() {
+        let token_program = anchor_spl::token::ID;
+        assert_eq!(check_mint(spl_token_mint(), token_program, token_program).unwrap(), DECIMALS);
+    }
+
+    #[test]
+    fn mint_decimals_reads_token_2022_mints() {
+        let token_program = anchor_spl::token_2022::ID;
+        let data = token_2022_mint(&[]);
+        assert_eq!(check_mint(data, token_program, token_program).unwrap(), DECIMALS);
+    }
+
+    #[test]
+    fn mint_decimals_rejects_mints_of_the_other_token_program() {
+        let (token, token_2022) = (anchor_spl::token::ID, anchor_spl::token_2022::ID);
+        assert_eq!(
+            check_mint(spl_token_mint(), token, token_2022).unwrap_err(),
+            CounterError::InvalidSwapAccount.into()
+        );
+        assert_eq!(
+            check_mint(token_2022_mint(&[]), token_2022, token).unwrap_err(),
+            CounterError::InvalidSwapAccount.into()
+        );
+    }
+
+    #[test]
+    fn mint_decimals_reads_transfer_hook_mints() {
+        let token_program = anchor_spl::token_2022::ID;
+        let data = token_2022_mint(&[ExtensionType::TransferHook]);
+        assert_eq!(check_mint(data, token_program, token_program).unwrap(), DECIMALS);
+    }
+}
@@ -40,4 +40,94 @@ pub enum CounterError {
 
     #[msg("Unauthorized recipient.")]  // ✅ Add this error
     UnauthorizedRecipient,
+
+    #[msg("Message is shorter than its header or declared body length.")]
+    MessageTooShort,
+
+    #[msg("Unsupported message version.")]
+    UnsupportedMessageVersion,
+
+    #[msg("Message has trailing bytes after its body.")]
+    TrailingBytes,
+
+    #[msg("Swap output is below the minimum amount out.")]
+    SlippageExceeded,
+
+    #[msg("Swap deadline has passed.")]
+    SwapExpired,
+
+    #[msg("No DEX adapter is registered for this dex choice.")]
+    UnknownDexChoice,
+
+    #[msg("DEX adapter is disabled.")]
+    DexDisabled,
+
+    #[msg("DEX address does not match the registered adapter.")]
+    DexAddressMismatch,
+
+    #[msg("Unsupported DEX adapter kind.")]
+    UnsupportedAdapterKind,
+
+    #[msg("Swap path exceeds the maximum number of hops.")]
+    PathTooLong,
+
+    #[msg("Swap path does not lead from token in to token out.")]
+    InvalidSwapPath,
+
+    #[msg("Signer is not the pending admin.")]
+    NotPendingAdmin,
+
+    #[msg("Program is paused.")]
+    Paused,
+
+    #[msg("Unauthorized: Only the admin or pauser can pause.")]
+    UnauthorizedPauser,
+
+    #[msg("Remote is disabled for this direction.")]
+    RemoteDisabled,
+
+    #[msg("Nonce is out of order for this remote.")]
+    InvalidNonce,
+
+    #[msg("No return accounts are set for this remote.")]
+    ReturnAccountsNotSet,
+
+    #[msg("Token-2022 transfer fee could not be computed.")]
+    TransferFeeFailed,
+
+    #[msg("Refund policy is unknown or needs an ABA message.")]
+    InvalidRefundPolicy,
+
+    #[msg("Receipt belongs to a failed message awaiting retry or discard.")]
+    FailedMessagePending,
+
+    #[msg("Encoded message body exceeds the maximum length.")]
+    MessageTooLarge,
+
+    #[msg("Swap account is missing or does not match the message.")]
+    InvalidSwapAccount,
+
+    #[msg("Mint has no transfer hook.")]
+    NoTransferHook,
+
+    #[msg("Failed to encode the outgoing message.")]
+    MessageEncodingFailed,
+
+    #[msg("Remote address must not be zero.")]
+    ZeroRemoteAddress,
+
+    #[msg("Vault holds less than the swap's amount in.")]
+    InsufficientVaultBalance,
+
+    #[msg("Signer is not the program's upgrade authority.")]
+    NotUpgradeAuthority,
+
+    #[msg("Receipt does not belong to a composed message awaiting lz_compose.")]
+    NotComposed,
+
+    #[msg("Receipt belongs to a composed message awaiting lz_compose.")]
+    ComposePending,
+
+    #[msg("LayerZero fee exceeds the fee agreed to.")]
+    FeeExceedsLimit,
 }
@@ -0,0 +1,239 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct AdminProposed {
+    pub count: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminChanged {
+    pub count: Pubkey,
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey, // Default pubkey once renounced
+}
+
+#[event]
+pub struct PauserSet {
+    pub count: Pubkey,
+    pub pauser: Pubkey,
+}
+
+#[event]
+pub struct PausedSet {
+    pub count: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct RemoteEnabledSet {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub inbound_enabled: bool,
+    pub outbound_enabled: bool,
+}
+
+#[event]
+pub struct RemoteRemoved {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub address: [u8; 32],
+}
+
+#[event]
+pub struct RemoteSet {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub address: [u8; 32],
+}
+
+#[event]
+pub struct MessageSent {
+    pub count: Pubkey,
+    pub dst_eid: u32,
+    pub guid: [u8; 32],
+    pub nonce: u64,
+    pub msg_type: u8,
+    pub native_fee: u64,
+    pub lz_token_fee: u64,
+}
+
+#[event]
+pub struct MessageReceived {
+    pub count: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub msg_type: u8,
+}
+
+#[event]
+pub struct SwapExecuted {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct SwapRefunded {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub token_in: Pubkey,
+    pub amount_in: u64,
+    pub recipient: Pubkey,
+    pub refund_policy: u8,
+    pub reason: u32, // Error code of the check that stopped the swap
+}
+
+#[event]
+pub struct MessageFailed {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub nonce: u64,
+    pub reason: u32, // Error code of the check that failed
+}
+
+#[event]
+pub struct MessageRetried {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+}
+
+#[event]
+pub struct MessageDiscarded {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+}
+
+#[event]
+pub struct MessageCleared {
+    pub count: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+}
+
+#[event]
+pub struct NonceSkipped {
+    pub count: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+}
+
+#[event]
+pub struct NonceNilified {
+    pub count: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+}
+
+#[event]
+pub struct NonceBurned {
+    pub count: Pubkey,
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+}
+
+#[event]
+pub struct ComposeSent {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub to: Pubkey,
+    pub index: u16,
+}
+
+#[event]
+pub struct ComposeReceived {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub from: Pubkey,
+    pub index: u16,
+}
+
+#[event]
+pub struct RemoteOrderedSet {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub ordered: bool,
+    pub inbound_nonce: u64,
+}
+
+#[event]
+pub struct RemoteNonBlockingSet {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub non_blocking: bool,
+}
+
+#[event]
+pub struct ReturnAccountsSet {
+    pub count: Pubkey,
+    pub eid: u32,
+    pub accounts_len: u32,
+}
+
+#[event]
+pub struct TransferHookAccountsSet {
+    pub count: Pubkey,
+    pub mint: Pubkey,
+    pub accounts_len: u32,
+}
+
+#[event]
+pub struct VaultInitialized {
+    pub count: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct Deposited {
+    pub count: Pubkey,
+    pub mint: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub count: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DexAdapterSet {
+    pub count: Pubkey,
+    pub dex_choice: u8,
+    pub program_id: Pubkey,
+    pub kind: u8,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct CountInitialized {
+    pub count: Pubkey,
+    pub id: u8,
+    pub admin: Pubkey,
+    pub endpoint: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct ReceiptClosed {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub outcome: u8,
+}
@@ -0,0 +1,26 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        constraint = count.pending_admin == Some(pending_admin.key()) @ CounterError::NotPendingAdmin
+    )]
+    pub pending_admin: Signer<'info>,
+    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl AcceptAdmin<'_> {
+    pub fn apply(ctx: &mut Context<AcceptAdmin>) -> Result<()> {
+        let old_admin = ctx.accounts.count.admin;
+        ctx.accounts.count.admin = ctx.accounts.pending_admin.key();
+        ctx.accounts.count.pending_admin = None;
+        emit!(AdminChanged {
+            count: ctx.accounts.count.key(),
+            old_admin,
+            new_admin: ctx.accounts.count.admin,
+        });
+        Ok(())
+    }
+}
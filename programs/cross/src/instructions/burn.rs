@@ -0,0 +1,60 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    cpi::accounts::Burn as EndpointBurn,
+    instructions::BurnParams as EndpointBurnParams,
+    ConstructCPIContext, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: BurnParams)]
+pub struct Burn<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.src_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = params.sender == remote.address
+    )]
+    pub remote: Account<'info, Remote>,
+}
+
+impl<'info> Burn<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Burn<'info>>,
+        params: &BurnParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        let cpi_ctx = EndpointBurn::construct_context(ENDPOINT_ID, ctx.remaining_accounts)?;
+        oapp::endpoint::cpi::burn(
+            cpi_ctx.with_signer(&[seeds]),
+            EndpointBurnParams {
+                receiver: ctx.accounts.count.key(),
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+                payload_hash: params.payload_hash,
+            },
+        )?;
+        ctx.accounts.remote.consume_nonce(params.nonce)?;
+        emit!(NonceBurned {
+            count: ctx.accounts.count.key(),
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct BurnParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub payload_hash: [u8; 32],
+}
@@ -0,0 +1,62 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{instructions::ClearParams as EndpointClearParams, ID as ENDPOINT_ID};
+
+#[derive(Accounts)]
+#[instruction(params: ClearParams)]
+pub struct Clear<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.src_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = params.sender == remote.address
+    )]
+    pub remote: Account<'info, Remote>,
+}
+
+impl<'info> Clear<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Clear<'info>>,
+        params: &ClearParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        oapp::endpoint_cpi::clear(
+            ENDPOINT_ID,
+            ctx.accounts.count.key(),
+            ctx.remaining_accounts,
+            seeds,
+            EndpointClearParams {
+                receiver: ctx.accounts.count.key(),
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+                guid: params.guid,
+                message: params.message.clone(),
+            },
+        )
+        .map_err(|_| CounterError::ClearFailed)?;
+        ctx.accounts.remote.consume_nonce(params.nonce)?;
+        emit!(MessageCleared {
+            count: ctx.accounts.count.key(),
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+            guid: params.guid,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ClearParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub guid: [u8; 32],
+    pub message: Vec<u8>,
+}
@@ -0,0 +1,39 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: CloseReceiptParams)]
+pub struct CloseReceipt<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    /// CHECK: the executor that funded the receipt, receives its rent
+    #[account(mut, address = receipt.payer)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [RECEIPT_SEED, &count.key().to_bytes(), &params.guid],
+        bump = receipt.bump,
+        constraint = receipt.outcome != OUTCOME_FAILED @ CounterError::FailedMessagePending,
+        constraint = receipt.outcome != OUTCOME_COMPOSED @ CounterError::ComposePending
+    )]
+    pub receipt: Account<'info, MessageReceipt>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl CloseReceipt<'_> {
+    pub fn apply(ctx: &mut Context<CloseReceipt>, params: &CloseReceiptParams) -> Result<()> {
+        emit!(ReceiptClosed {
+            count: ctx.accounts.count.key(),
+            guid: params.guid,
+            outcome: ctx.accounts.receipt.outcome,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct CloseReceiptParams {
+    pub guid: [u8; 32],
+}
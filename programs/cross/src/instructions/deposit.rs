@@ -0,0 +1,54 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Transfer hook mints take the hook's extra accounts as remaining accounts.
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub depositor: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, token::mint = mint, token::authority = depositor)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, associated_token::mint = mint,
+        associated_token::authority = count,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Deposit<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Deposit<'info>>,
+        params: &DepositParams,
+    ) -> Result<()> {
+        dex::transfer_checked(
+            dex::TransferAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                from: &ctx.accounts.source.to_account_info(),
+                mint: &ctx.accounts.mint.to_account_info(),
+                to: &ctx.accounts.vault.to_account_info(),
+                authority: &ctx.accounts.depositor.to_account_info(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            params.amount,
+            ctx.accounts.mint.decimals,
+            &[],
+        )?;
+        emit!(Deposited {
+            count: ctx.accounts.count.key(),
+            mint: ctx.accounts.mint.key(),
+            depositor: ctx.accounts.depositor.key(),
+            amount: params.amount,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DepositParams {
+    pub amount: u64,
+}
@@ -0,0 +1,40 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: DiscardMessageParams)]
+pub struct DiscardMessage<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [FAILED_MESSAGE_SEED, &count.key().to_bytes(), &params.guid],
+        bump = failed_message.bump
+    )]
+    pub failed_message: Account<'info, FailedMessage>,
+    /// CHECK: paid the failed message's rent, refunded on discard
+    #[account(mut, address = failed_message.payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, &count.key().to_bytes(), &params.guid],
+        bump = receipt.bump
+    )]
+    pub receipt: Account<'info, MessageReceipt>,
+}
+
+impl DiscardMessage<'_> {
+    pub fn apply(ctx: &mut Context<DiscardMessage>, params: &DiscardMessageParams) -> Result<()> {
+        ctx.accounts.receipt.outcome = OUTCOME_DISCARDED;
+        emit!(MessageDiscarded { count: ctx.accounts.count.key(), guid: params.guid });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct DiscardMessageParams {
+    pub guid: [u8; 32],
+}
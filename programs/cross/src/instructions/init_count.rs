@@ -0,0 +1,89 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{instructions::RegisterOAppParams, ID as ENDPOINT_ID};
+
+/// Only the program's upgrade authority may create a Count, which registers as the OApp and
+/// picks its admin.
+#[derive(Accounts)]
+#[instruction(params: InitCountParams)]
+pub struct InitCount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Lzreceiver>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ CounterError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = payer,
+        space = Count::SIZE,
+        seeds = [COUNT_SEED, &params.id.to_be_bytes()],
+        bump
+    )]
+    pub count: Account<'info, Count>,
+    #[account(
+        init,
+        payer = payer,
+        space = LzReceiveTypesAccounts::SIZE,
+        seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()],
+        bump
+    )]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(
+        init,
+        payer = payer,
+        space = LzReceiveTypesAccounts::SIZE,
+        seeds = [LZ_COMPOSE_TYPES_SEED, &count.key().to_bytes()],
+        bump
+    )]
+    pub lz_compose_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    pub system_program: Program<'info, System>,
+}
+
+impl InitCount<'_> {
+    pub fn apply(ctx: &mut Context<InitCount>, params: &InitCountParams) -> Result<()> {
+        require_keys_eq!(params.endpoint, ENDPOINT_ID, CounterError::InvalidEndpointSettings);
+
+        ctx.accounts.count.id = params.id;
+        ctx.accounts.count.admin = params.admin;
+        ctx.accounts.count.bump = ctx.bumps.count;
+        ctx.accounts.count.endpoint_program = params.endpoint;
+        ctx.accounts.count.pending_admin = None;
+        ctx.accounts.count.pauser = params.admin;
+        ctx.accounts.count.paused = false;
+
+        ctx.accounts.lz_receive_types_accounts.count = ctx.accounts.count.key();
+        ctx.accounts.lz_compose_types_accounts.count = ctx.accounts.count.key();
+
+        // Register the Count PDA as an OApp, with `delegate` allowed to manage its endpoint config
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        oapp::endpoint_cpi::register_oapp(
+            ENDPOINT_ID,
+            ctx.accounts.count.key(),
+            ctx.remaining_accounts,
+            seeds,
+            RegisterOAppParams { delegate: params.delegate },
+        )?;
+
+        emit!(CountInitialized {
+            count: ctx.accounts.count.key(),
+            id: params.id,
+            admin: params.admin,
+            endpoint: params.endpoint,
+            delegate: params.delegate,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct InitCountParams {
+    pub id: u8,
+    pub admin: Pubkey,
+    pub endpoint: Pubkey, // Has to be the endpoint the program is built against
+    pub delegate: Pubkey,
+}
@@ -0,0 +1,62 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+pub struct InitVault<'info> {
+    #[account(mut, address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    // Record Token-2022 mints so `lz_receive_types` and `lz_compose_types` can derive their
+    // accounts
+    #[account(mut, seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(mut, seeds = [LZ_COMPOSE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_compose_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    // Vaults are the Count PDA's associated token accounts, one per mint
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = count,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl InitVault<'_> {
+    pub fn apply(ctx: &mut Context<InitVault>) -> Result<()> {
+        let mint = ctx.accounts.mint.key();
+        let is_token_2022 = ctx.accounts.token_program.key() == token_2022::ID;
+        for types_accounts in [
+            &mut ctx.accounts.lz_receive_types_accounts,
+            &mut ctx.accounts.lz_compose_types_accounts,
+        ] {
+            if is_token_2022 && !types_accounts.token_2022_mints.contains(&mint) {
+                types_accounts.token_2022_mints.push(mint);
+                LzReceiveTypesAccounts::grow_to_fit(
+                    types_accounts,
+                    &ctx.accounts.admin.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                )?;
+            }
+        }
+
+        emit!(VaultInitialized {
+            count: ctx.accounts.count.key(),
+            mint,
+            vault: ctx.accounts.vault.key(),
+        });
+        Ok(())
+    }
+}
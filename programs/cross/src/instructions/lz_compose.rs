@@ -0,0 +1,135 @@
+use crate::settlement::{InboundMessage, Settlement};
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use oapp::{
+    endpoint::{
+        cpi::accounts::ClearCompose, instructions::ClearComposeParams, state::EndpointSettings,
+        ConstructCPIContext, ENDPOINT_SEED, ID as ENDPOINT_ID,
+    },
+    LzComposeParams,
+};
+
+/// Second stage of a composed swap: `LzReceive` clears the message and queues it, this settles
+/// it. The swap accounts follow the endpoint accounts as remaining accounts, as for `LzReceive`.
+#[derive(Accounts)]
+#[instruction(params: LzComposeParams)]
+pub struct LzCompose<'info> {
+    /// Executor, pays for the recipient's token account if missing
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Paused compose messages are not cleared and stay retryable in the endpoint
+    #[account(
+        mut,
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
+    pub count: Account<'info, Count>,
+
+    // Written by `LzReceive` for the message this compose message queued
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, &count.key().to_bytes(), &params.guid],
+        bump = receipt.bump,
+        constraint = receipt.outcome == OUTCOME_COMPOSED @ CounterError::NotComposed
+    )]
+    pub receipt: Box<Account<'info, MessageReceipt>>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &receipt.src_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = receipt.sender == remote.address,
+        constraint = remote.inbound_enabled @ CounterError::RemoteDisabled
+    )]
+    pub remote: Account<'info, Remote>,
+    /// CHECK: only created, by `Settlement::settle`, when a non-blocking remote's swap fails
+    #[account(
+        mut,
+        seeds = [FAILED_MESSAGE_SEED, &count.key().to_bytes(), &params.guid],
+        bump
+    )]
+    pub failed_message: UncheckedAccount<'info>,
+
+    // Locates the return leg accounts of ABA swaps
+    #[account(seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Box<Account<'info, LzReceiveTypesAccounts>>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Box<Account<'info, EndpointSettings>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LzCompose<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, LzCompose<'info>>,
+        params: &LzComposeParams,
+    ) -> Result<()> {
+        // Only compose messages this OApp queued for itself in `LzReceive::apply` are accepted
+        require!(params.from == ctx.accounts.count.key(), CounterError::UnauthorizedSender);
+        // `LzReceive::apply` queues the swap message itself, only for the composed types
+        let msg_type = msg_codec::msg_type(&params.message)?;
+        require!(msg_codec::is_composed(msg_type), CounterError::InvalidMessageType);
+
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+
+        // Clear the compose message first
+        let accounts_for_clear_compose = &ctx.remaining_accounts[0..ClearCompose::MIN_ACCOUNTS_LEN];
+        oapp::endpoint_cpi::clear_compose(
+            ENDPOINT_ID,
+            ctx.accounts.count.key(),
+            accounts_for_clear_compose,
+            seeds,
+            ClearComposeParams {
+                from: params.from,
+                guid: params.guid,
+                index: params.index,
+                message: params.message.clone(),
+            },
+        ).map_err(|_| CounterError::ClearFailed)?;
+
+        ctx.accounts.count.composed_count += 1;
+        emit!(ComposeReceived {
+            count: ctx.accounts.count.key(),
+            guid: params.guid,
+            from: params.from,
+            index: params.index,
+        });
+
+        let receipt = &ctx.accounts.receipt;
+        let message = InboundMessage {
+            guid: params.guid,
+            src_eid: receipt.src_eid,
+            sender: receipt.sender,
+            nonce: receipt.nonce,
+            message: &params.message,
+        };
+        let remaining_accounts = &ctx.remaining_accounts[ClearCompose::MIN_ACCOUNTS_LEN..];
+        let failed_message = ctx.accounts.failed_message.to_account_info();
+        let bump = ctx.bumps.failed_message;
+        ctx.accounts.settlement(ctx.program_id).settle(
+            &message,
+            remaining_accounts,
+            &failed_message,
+            bump,
+            seeds,
+        )
+    }
+
+    fn settlement<'a>(&'a mut self, program_id: &'a Pubkey) -> Settlement<'a, 'info> {
+        Settlement {
+            program_id,
+            payer: &self.payer,
+            count: &self.count,
+            remote: &mut self.remote,
+            receipt: &mut self.receipt,
+            lz_receive_types_accounts: &self.lz_receive_types_accounts,
+            endpoint: &self.endpoint,
+            associated_token_program: &self.associated_token_program,
+            system_program: &self.system_program,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token;
+use oapp::endpoint_cpi::{get_accounts_for_clear_compose, LzAccount};
+use oapp::{
+    endpoint::{ENDPOINT_SEED, ID as ENDPOINT_ID},
+    LzComposeParams,
+};
+
+#[derive(Accounts)]
+pub struct LzComposeTypes<'info> {
+    #[account(
+        seeds = [LZ_COMPOSE_TYPES_SEED, &lz_compose_types_accounts.count.to_bytes()],
+        bump
+    )]
+    pub lz_compose_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+}
+
+impl LzComposeTypes<'_> {
+    /// Returns the accounts `LzCompose` expects, as listed by `compose_accounts`.
+    pub fn apply(
+        ctx: &Context<LzComposeTypes>,
+        params: &LzComposeParams,
+    ) -> Result<Vec<LzAccount>> {
+        compose_accounts(ctx.program_id, &ctx.accounts.lz_compose_types_accounts, params)
+    }
+}
+
+/// Returns the accounts `LzCompose` expects, in order: payer, Count, receipt, Remote, failed
+/// message, the receive types account, endpoint settings, the associated token and system
+/// programs, the endpoint accounts for `clear_compose`, then the swap's `swap_accounts`.
+pub fn compose_accounts(
+    program_id: &Pubkey,
+    types: &LzReceiveTypesAccounts,
+    params: &LzComposeParams,
+) -> Result<Vec<LzAccount>> {
+    let count = types.count;
+    // `LzReceive` only queues swap messages that decode, with the src_eid it checked
+    let msg_type = msg_codec::msg_type(&params.message)?;
+    let swap_params = msg_codec::decode_swap(&params.message)?;
+    let src_eid = msg_codec::src_eid(&params.message)?;
+
+    let receipt_seeds = [RECEIPT_SEED, &count.to_bytes(), &params.guid[..]];
+    let (receipt, _) = Pubkey::find_program_address(&receipt_seeds, program_id);
+
+    let remote_seeds = [REMOTE_SEED, &count.to_bytes(), &src_eid.to_be_bytes()];
+    let (remote, _) = Pubkey::find_program_address(&remote_seeds, program_id);
+
+    let failed_message_seeds = [FAILED_MESSAGE_SEED, &count.to_bytes(), &params.guid[..]];
+    let (failed_message, _) = Pubkey::find_program_address(&failed_message_seeds, program_id);
+
+    let receive_types_seeds = [LZ_RECEIVE_TYPES_SEED, &count.to_bytes()];
+    let (receive_types, _) = Pubkey::find_program_address(&receive_types_seeds, program_id);
+
+    let (endpoint, _) = Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID);
+
+    let mut accounts = vec![
+        // Default pubkey signer is replaced with the executor
+        LzAccount { pubkey: Pubkey::default(), is_signer: true, is_writable: true }, // payer
+        LzAccount { pubkey: count, is_signer: false, is_writable: true },  // Count account
+        LzAccount { pubkey: receipt, is_signer: false, is_writable: true },
+        LzAccount { pubkey: remote, is_signer: false, is_writable: true },  // Remote account
+        LzAccount { pubkey: failed_message, is_signer: false, is_writable: true },
+        LzAccount { pubkey: receive_types, is_signer: false, is_writable: false },
+        LzAccount { pubkey: endpoint, is_signer: false, is_writable: false },
+        LzAccount { pubkey: associated_token::ID, is_signer: false, is_writable: false },
+        LzAccount { pubkey: system_program::ID, is_signer: false, is_writable: false },
+    ];
+
+    let accounts_for_clear_compose = get_accounts_for_clear_compose(
+        ENDPOINT_ID,
+        &params.from,
+        &count,
+        &params.guid,
+        params.index,
+        &params.message,
+    );
+    accounts.extend(accounts_for_clear_compose);
+    accounts.extend(swap_accounts(program_id, types, src_eid, msg_type, &swap_params));
+
+    Ok(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg_codec::tests::swap_params;
+
+    const SRC_EID: u32 = 30101;
+
+    #[test]
+    fn leads_with_the_accounts_lz_compose_validates_then_the_swap_accounts() {
+        let types = LzReceiveTypesAccounts {
+            count: Pubkey::new_unique(),
+            return_accounts: vec![],
+            token_2022_mints: vec![],
+            transfer_hook_accounts: vec![],
+        };
+        let swap_params = swap_params();
+        let message =
+            msg_codec::encode_swap(msg_codec::COMPOSED_TYPE, SRC_EID, &swap_params, None).unwrap();
+        let params = LzComposeParams {
+            from: types.count,
+            to: types.count,
+            guid: [2; 32],
+            index: 0,
+            message,
+            extra_data: vec![],
+        };
+        let accounts = compose_accounts(&crate::ID, &types, &params).unwrap();
+
+        let count = types.count;
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+        let lz_compose = crate::accounts::LzCompose {
+            payer: Pubkey::default(),
+            count,
+            receipt: pda(&[RECEIPT_SEED, &count.to_bytes(), &params.guid]),
+            remote: pda(&[REMOTE_SEED, &count.to_bytes(), &SRC_EID.to_be_bytes()]),
+            failed_message: pda(&[FAILED_MESSAGE_SEED, &count.to_bytes(), &params.guid]),
+            lz_receive_types_accounts: pda(&[LZ_RECEIVE_TYPES_SEED, &count.to_bytes()]),
+            endpoint: Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID).0,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let metas = lz_compose.to_account_metas(None);
+        for (account, meta) in accounts.iter().zip(&metas) {
+            assert_eq!(account.pubkey, meta.pubkey);
+            assert_eq!(account.is_signer, meta.is_signer, "{}", meta.pubkey);
+            assert_eq!(account.is_writable, meta.is_writable, "{}", meta.pubkey);
+        }
+
+        let accounts_for_clear_compose = get_accounts_for_clear_compose(
+            ENDPOINT_ID,
+            &params.from,
+            &count,
+            &params.guid,
+            params.index,
+            &params.message,
+        );
+        let swap_accounts = &accounts[metas.len() + accounts_for_clear_compose.len()..];
+        assert_eq!(swap_accounts.len(), crate::settlement::SWAP_ACCOUNTS_LEN);
+        assert_eq!(swap_accounts[0].pubkey.to_bytes(), swap_params.token_in);
+        assert_eq!(swap_accounts[1].pubkey.to_bytes(), swap_params.token_out);
+    }
+}
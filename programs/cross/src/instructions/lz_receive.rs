@@ -1,87 +1,92 @@
+use crate::settlement::{InboundMessage, Settlement};
 use crate::*;
 use anchor_lang::prelude::*;
-use bincode::{config, decode_from_slice, Encode, Decode}; // ✅ Import bincode traits
+use anchor_spl::associated_token::AssociatedToken;
 use oapp::{
     endpoint::{
-        cpi::accounts::Clear,
-        instructions::{ClearParams, SendComposeParams},
-        ConstructCPIContext, ID as ENDPOINT_ID,
+        cpi::accounts::{Clear as EndpointClear, SendCompose},
+        instructions::{ClearParams as EndpointClearParams, SendComposeParams},
+        state::EndpointSettings,
+        ConstructCPIContext, ENDPOINT_SEED, ID as ENDPOINT_ID,
     },
     LzReceiveParams,
 };
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Encode, Decode)] 
-pub struct SwapParams {
-    pub token_in: [u8; 32],  
-    pub token_out: [u8; 32], 
-    pub amount_in: u64,
-    pub min_amount_out: u64,
-    pub path: Vec<[u8; 32]>,  
-    pub dex_choice: u8,
-    pub deadline: u64,
-    pub dex_address: [u8; 32],  
-    pub recipient: [u8; 32],    
-    pub fee: u32,
-    pub sqrt_price_limit_x96: u128,
-}
-
+/// Only takes the accounts every message needs. The swap accounts follow the endpoint
+/// accounts as remaining accounts and are loaded by `Settlement::check`, so a message naming
+/// accounts that don't match can still be cleared and stored by a non-blocking remote.
 #[derive(Accounts)]
 #[instruction(params: LzReceiveParams)]
 pub struct LzReceive<'info> {
-    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    /// Executor, pays for the receipt and, if missing, the recipient's token account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // Paused messages are not cleared and stay retryable in the endpoint
+    #[account(
+        mut,
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
     pub count: Account<'info, Count>,
     
     #[account(
+        mut,
         seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.src_eid.to_be_bytes()],
         bump = remote.bump,
-        constraint = params.sender == remote.address
+        constraint = params.sender == remote.address,
+        constraint = remote.inbound_enabled @ CounterError::RemoteDisabled
     )]
     pub remote: Account<'info, Remote>,
-}
 
-impl LzReceive<'_> {
-    pub fn apply(ctx: &mut Context<LzReceive>, params: &LzReceiveParams) -> Result<()> {
-        let seeds: &[&[u8]] =
-            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
-
-        // Decode SwapParams from message
-        let swap_params: SwapParams = match decode_from_slice(&params.message, config::standard()) {
-            Ok((decoded, _)) => decoded, 
-            Err(_) => return Err(CounterError::InvalidMessageType.into()),
-        };
-
-        // Convert `[u8; 32]` back to `Pubkey`
-        let token_in_pubkey = Pubkey::new_from_array(swap_params.token_in);
-        let recipient_pubkey = Pubkey::new_from_array(swap_params.recipient);
+    // Created once per guid. Replays are stopped by the endpoint's clear, not this account, as
+    // the receipt can be closed afterwards
+    #[account(
+        init,
+        payer = payer,
+        space = MessageReceipt::SIZE,
+        seeds = [RECEIPT_SEED, &count.key().to_bytes(), &params.guid],
+        bump
+    )]
+    pub receipt: Box<Account<'info, MessageReceipt>>,
+    /// CHECK: only created, by `store_failed`, when a non-blocking remote's message fails
+    #[account(
+        mut,
+        seeds = [FAILED_MESSAGE_SEED, &count.key().to_bytes(), &params.guid],
+        bump
+    )]
+    pub failed_message: UncheckedAccount<'info>,
 
-        let expected_recipient_pubkey = ctx.accounts.count.key();
+    // Locates the return leg accounts of ABA swaps
+    #[account(seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Box<Account<'info, LzReceiveTypesAccounts>>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Box<Account<'info, EndpointSettings>>,
 
-        // Verify sender
-        require!(
-            params.sender == ctx.accounts.remote.address,
-            CounterError::UnauthorizedSender
-        );
-        require!(
-            recipient_pubkey == expected_recipient_pubkey, 
-            CounterError::UnauthorizedRecipient
-        );
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
 
-        // Verify recipient
-require!(
-    recipient_pubkey == expected_recipient_pubkey, 
-    CounterError::UnauthorizedRecipient
-);
+impl<'info> LzReceive<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, LzReceive<'info>>,
+        params: &LzReceiveParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
 
-        
+        // Ordered peers must deliver nonces one after another
+        ctx.accounts.remote.consume_nonce(params.nonce)?;
 
         // Clear the message first
-        let accounts_for_clear = &ctx.remaining_accounts[0..Clear::MIN_ACCOUNTS_LEN];
+        let accounts_for_clear = &ctx.remaining_accounts[0..EndpointClear::MIN_ACCOUNTS_LEN];
         oapp::endpoint_cpi::clear(
             ENDPOINT_ID,
             ctx.accounts.count.key(),
             accounts_for_clear,
             seeds,
-            ClearParams {
+            EndpointClearParams {
                 receiver: ctx.accounts.count.key(),
                 src_eid: params.src_eid,
                 sender: params.sender,
@@ -90,42 +95,102 @@ require!(
                 message: params.message.clone(),
             },
         ).map_err(|_| CounterError::ClearFailed)?;
+        let mut accounts_used = EndpointClear::MIN_ACCOUNTS_LEN;
+
+        // Zero for anything but a swap message, which `Settlement::check` then rejects
+        let msg_type = msg_codec::msg_type(&params.message).unwrap_or_default();
+        emit!(MessageReceived {
+            count: ctx.accounts.count.key(),
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+            guid: params.guid,
+            msg_type,
+        });
+        ctx.accounts.count.count += 1;
+        ctx.accounts.remote.messages_received += 1;
+
+        // `lz_receive_types` only passes the compose accounts for messages that decode
+        let composed =
+            msg_codec::is_composed(msg_type) && msg_codec::decode_swap(&params.message).is_ok();
+        if composed {
+            let accounts_for_compose = &ctx.remaining_accounts
+                [accounts_used..accounts_used + SendCompose::MIN_ACCOUNTS_LEN];
+            oapp::endpoint_cpi::send_compose(
+                ENDPOINT_ID,
+                ctx.accounts.count.key(),
+                accounts_for_compose,
+                seeds,
+                SendComposeParams {
+                    to: ctx.accounts.count.key(), // self
+                    guid: params.guid,
+                    index: 0,
+                    message: params.message.clone(),
+                },
+            ).map_err(|_| CounterError::SendComposeFailed)?;
+            accounts_used += SendCompose::MIN_ACCOUNTS_LEN;
+            ctx.accounts.remote.composed_sent += 1;
+            emit!(ComposeSent {
+                count: ctx.accounts.count.key(),
+                guid: params.guid,
+                to: ctx.accounts.count.key(),
+                index: 0,
+            });
+        }
 
-        // Process message type
-        let msg_type = msg_codec::msg_type(&params.message);
-        match msg_type {
-            msg_codec::VANILLA_TYPE => {
-                ctx.accounts.count.count += 1;
-                msg!("Received Vanilla Swap Message!");
-            }
-            msg_codec::COMPOSED_TYPE => {
-                ctx.accounts.count.count += 1;
-                msg!("Received Composed Swap Message! Sending response...");
-
-                oapp::endpoint_cpi::send_compose(
-                    ENDPOINT_ID,
-                    ctx.accounts.count.key(),
-                    &ctx.remaining_accounts[Clear::MIN_ACCOUNTS_LEN..],
-                    seeds,
-                    SendComposeParams {
-                        to: ctx.accounts.count.key(), // self
-                        guid: params.guid,
-                        index: 0,
-                        message: params.message.clone(),
-                    },
-                ).map_err(|_| CounterError::SendComposeFailed)?;
-            }
-            _ => return Err(CounterError::InvalidMessageType.into()),
+        let slot = Clock::get()?.slot;
+
+        let remote = &mut ctx.accounts.remote;
+        remote.last_nonce = params.nonce;
+        remote.last_received_slot = slot;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.guid = params.guid;
+        receipt.src_eid = params.src_eid;
+        receipt.sender = params.sender;
+        receipt.nonce = params.nonce;
+        receipt.msg_type = msg_type;
+        receipt.slot = slot;
+        receipt.payer = ctx.accounts.payer.key();
+        receipt.bump = ctx.bumps.receipt;
+
+        // Composed swaps settle in `lz_compose`, with the accounts `lz_compose_types` resolves
+        if composed {
+            receipt.outcome = OUTCOME_COMPOSED;
+            return Ok(());
         }
 
-        msg!(
-            " Received Swap Message: TokenIn: {:?}, TokenOut: {:?}, AmountIn: {:?}",
-            token_in_pubkey,
-            Pubkey::new_from_array(swap_params.token_out),
-            swap_params.amount_in
-        );
+        let message = InboundMessage {
+            guid: params.guid,
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+            message: &params.message,
+        };
+        let remaining_accounts = &ctx.remaining_accounts[accounts_used..];
+        let failed_message = ctx.accounts.failed_message.to_account_info();
+        let bump = ctx.bumps.failed_message;
+        ctx.accounts.settlement(ctx.program_id).settle(
+            &message,
+            remaining_accounts,
+            &failed_message,
+            bump,
+            seeds,
+        )
+    }
 
-        Ok(())
+    fn settlement<'a>(&'a mut self, program_id: &'a Pubkey) -> Settlement<'a, 'info> {
+        Settlement {
+            program_id,
+            payer: &self.payer,
+            count: &self.count,
+            remote: &mut self.remote,
+            receipt: &mut self.receipt,
+            lz_receive_types_accounts: &self.lz_receive_types_accounts,
+            endpoint: &self.endpoint,
+            associated_token_program: &self.associated_token_program,
+            system_program: &self.system_program,
+        }
     }
 }
 
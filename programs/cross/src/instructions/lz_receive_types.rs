@@ -1,53 +1,404 @@
+use crate::msg_codec::SwapParams;
 use crate::*;
+use anchor_lang::solana_program::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
 use oapp::endpoint_cpi::{get_accounts_for_clear, get_accounts_for_send_compose, LzAccount};
-use oapp::{endpoint::ID as ENDPOINT_ID, LzReceiveParams};
+use oapp::{
+    endpoint::{ENDPOINT_SEED, ID as ENDPOINT_ID},
+    LzReceiveParams,
+};
 
 #[derive(Accounts)]
-pub struct LzReceiveTypes {}
+pub struct LzReceiveTypes<'info> {
+    #[account(
+        seeds = [LZ_RECEIVE_TYPES_SEED, &lz_receive_types_accounts.count.to_bytes()],
+        bump
+    )]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+}
 
-impl LzReceiveTypes {
+impl LzReceiveTypes<'_> {
+    /// Returns the accounts `LzReceive` expects, as listed by `receive_accounts`.
     pub fn apply(
         ctx: &Context<LzReceiveTypes>,
         params: &LzReceiveParams,
     ) -> Result<Vec<LzAccount>> {
-        let count_seeds = [COUNT_SEED, &params.src_eid.to_be_bytes()];
-        
-        let (count, count_bump) = Pubkey::find_program_address(&count_seeds, ctx.program_id);
-
-        let remote_seeds = [REMOTE_SEED, &count.to_bytes(), &params.src_eid.to_be_bytes()];
-        let (remote, _) = Pubkey::find_program_address(&remote_seeds, ctx.program_id);
-
-        let mut accounts = vec![
-            LzAccount { pubkey: count, is_signer: false, is_writable: true },  // Count account
-            LzAccount { pubkey: remote, is_signer: false, is_writable: false }, // Remote account
-        ];
+        let types = &ctx.accounts.lz_receive_types_accounts;
+        Ok(receive_accounts(ctx.program_id, &types.key(), types, params))
+    }
+}
 
-        let accounts_for_clear = get_accounts_for_clear(
+/// Returns the accounts `LzReceive` expects, in order: payer, Count, Remote, receipt, failed
+/// message, the types account at `types_key`, endpoint settings, the associated token and
+/// system programs, then the endpoint accounts for `clear`. A composed swap goes on with the
+/// endpoint accounts for `send_compose`, as `lz_compose` settles it. Any other swap goes on
+/// with its `swap_accounts`. A message that isn't a swap stops after `clear`, so it can still
+/// be cleared and stored as failed.
+pub fn receive_accounts(
+    program_id: &Pubkey,
+    types_key: &Pubkey,
+    types: &LzReceiveTypesAccounts,
+    params: &LzReceiveParams,
+) -> Vec<LzAccount> {
+    // Count is seeded by its store id, so take its address from the types account
+    let count = types.count;
+
+    let remote_seeds = [REMOTE_SEED, &count.to_bytes(), &params.src_eid.to_be_bytes()];
+    let (remote, _) = Pubkey::find_program_address(&remote_seeds, program_id);
+
+    let receipt_seeds = [RECEIPT_SEED, &count.to_bytes(), &params.guid[..]];
+    let (receipt, _) = Pubkey::find_program_address(&receipt_seeds, program_id);
+
+    let failed_message_seeds = [FAILED_MESSAGE_SEED, &count.to_bytes(), &params.guid[..]];
+    let (failed_message, _) = Pubkey::find_program_address(&failed_message_seeds, program_id);
+
+    let (endpoint, _) = Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID);
+
+    let mut accounts = vec![
+        // Default pubkey signer is replaced with the executor
+        LzAccount { pubkey: Pubkey::default(), is_signer: true, is_writable: true }, // payer
+        LzAccount { pubkey: count, is_signer: false, is_writable: true },  // Count account
+        LzAccount { pubkey: remote, is_signer: false, is_writable: true },  // Remote account
+        LzAccount { pubkey: receipt, is_signer: false, is_writable: true },
+        LzAccount { pubkey: failed_message, is_signer: false, is_writable: true },
+        LzAccount { pubkey: *types_key, is_signer: false, is_writable: false },
+        LzAccount { pubkey: endpoint, is_signer: false, is_writable: false },
+        LzAccount { pubkey: associated_token::ID, is_signer: false, is_writable: false },
+        LzAccount { pubkey: system_program::ID, is_signer: false, is_writable: false },
+    ];
+
+    let accounts_for_clear = get_accounts_for_clear(
+        ENDPOINT_ID,
+        &count,
+        params.src_eid,
+        &params.sender,
+        params.nonce,
+    );
+    accounts.extend(accounts_for_clear);
+
+    let (Ok(msg_type), Ok(swap_params)) =
+        (msg_codec::msg_type(&params.message), msg_codec::decode_swap(&params.message))
+    else {
+        return accounts;
+    };
+
+    if msg_codec::is_composed(msg_type) {
+        let accounts_for_composing = get_accounts_for_send_compose(
             ENDPOINT_ID,
             &count,
-            params.src_eid,
-            &params.sender,
-            params.nonce,
+            &count, // self
+            &params.guid,
+            0,
+            &params.message,
         );
-        accounts.extend(accounts_for_clear);
-
-        let is_composed = msg_codec::msg_type(&params.message) == msg_codec::COMPOSED_TYPE;
-        if is_composed {
-            let accounts_for_composing = get_accounts_for_send_compose(
-                ENDPOINT_ID,
-                &count,
-                &count, // self
-                &params.guid,
-                0,
-                &params.message,
-            );
-            accounts.extend(accounts_for_composing);
+        accounts.extend(accounts_for_composing);
+    } else {
+        accounts.extend(swap_accounts(program_id, types, params.src_eid, msg_type, &swap_params));
+    }
+    accounts
+}
+
+/// Returns the accounts `Settlement::check` takes for a swap from `src_eid`: the swap accounts
+/// it loads, the vaults for intermediate hops, the return leg accounts of ABA swaps, then the
+/// transfer hook accounts of `token_in` and `token_out`.
+pub fn swap_accounts(
+    program_id: &Pubkey,
+    types: &LzReceiveTypesAccounts,
+    src_eid: u32,
+    msg_type: u8,
+    swap_params: &SwapParams,
+) -> Vec<LzAccount> {
+    let count = types.count;
+    let token_in_mint = Pubkey::new_from_array(swap_params.token_in);
+    let token_out_mint = Pubkey::new_from_array(swap_params.token_out);
+    let recipient = Pubkey::new_from_array(swap_params.recipient);
+    // Mint owners can't be read here, vaults record which mints live under Token-2022
+    let ata = |owner: &Pubkey, mint: &Pubkey| {
+        get_associated_token_address_with_program_id(owner, mint, &types.token_program(mint))
+    };
+
+    let dex_adapter_seeds =
+        [DEX_ADAPTER_SEED, &count.to_bytes(), &swap_params.dex_choice.to_be_bytes()];
+    let (dex_adapter, _) = Pubkey::find_program_address(&dex_adapter_seeds, program_id);
+
+    // Swap accounts, in the order `Settlement::check` loads them
+    let mut accounts = vec![
+        LzAccount { pubkey: token_in_mint, is_signer: false, is_writable: false },
+        LzAccount { pubkey: token_out_mint, is_signer: false, is_writable: false },
+        LzAccount {
+            pubkey: ata(&count, &token_in_mint),
+            is_signer: false,
+            is_writable: true,
+        }, // token_in vault
+        LzAccount {
+            pubkey: ata(&count, &token_out_mint),
+            is_signer: false,
+            is_writable: true,
+        }, // token_out vault
+        LzAccount { pubkey: recipient, is_signer: false, is_writable: false },
+        LzAccount {
+            pubkey: ata(&recipient, &token_out_mint),
+            is_signer: false,
+            is_writable: true,
+        }, // recipient token account
+        LzAccount {
+            pubkey: ata(&recipient, &token_in_mint),
+            is_signer: false,
+            is_writable: true,
+        }, // recipient refund account
+        LzAccount { pubkey: dex_adapter, is_signer: false, is_writable: false },
+        LzAccount {
+            pubkey: Pubkey::new_from_array(swap_params.dex_address),
+            is_signer: false,
+            is_writable: false,
+        },
+        LzAccount {
+            pubkey: types.token_program(&token_in_mint),
+            is_signer: false,
+            is_writable: false,
+        },
+        LzAccount {
+            pubkey: types.token_program(&token_out_mint),
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+
+    // Vaults for the intermediate mints of a multi-hop route
+    if let Ok(route) = dex::route(swap_params) {
+        accounts.extend(route[1..route.len() - 1].iter().map(|mint| LzAccount {
+            pubkey: ata(&count, mint),
+            is_signer: false,
+            is_writable: true,
+        }));
+    }
+
+    // Accounts the result of an ABA swap is sent back through
+    if msg_codec::is_aba(msg_type) {
+        if let Some(accounts_for_return) = types.return_accounts(src_eid) {
+            accounts.extend(accounts_for_return.iter().cloned());
         }
+    }
 
-        Ok(accounts)
+    // Extra accounts of the transfer hooks the payout or a refund may run through
+    for mint in [&token_in_mint, &token_out_mint] {
+        accounts.extend(types.transfer_hook_accounts(mint).iter().cloned());
     }
+
+    accounts
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg_codec::tests::swap_params;
+    use anchor_spl::{token, token_2022};
+
+    const SRC_EID: u32 = 30101;
+    // Payer through the system program, ahead of the endpoint accounts
+    const CORE_ACCOUNTS_LEN: usize = 9;
+
+    fn receive_params(message: Vec<u8>) -> LzReceiveParams {
+        LzReceiveParams {
+            src_eid: SRC_EID,
+            sender: [1; 32],
+            nonce: 1,
+            guid: [2; 32],
+            message,
+            extra_data: vec![],
+        }
+    }
+
+    fn swap(msg_type: u8, swap_params: &SwapParams) -> Vec<u8> {
+        msg_codec::encode_swap(msg_type, SRC_EID, swap_params, None).unwrap()
+    }
+
+    fn types_accounts(token_2022_mints: Vec<Pubkey>) -> LzReceiveTypesAccounts {
+        LzReceiveTypesAccounts {
+            count: Pubkey::new_unique(),
+            return_accounts: vec![],
+            token_2022_mints,
+            transfer_hook_accounts: vec![],
+        }
+    }
+
+    fn accounts_for_clear(
+        types: &LzReceiveTypesAccounts,
+        params: &LzReceiveParams,
+    ) -> Vec<LzAccount> {
+        get_accounts_for_clear(ENDPOINT_ID, &types.count, SRC_EID, &params.sender, params.nonce)
+    }
+
+    /// The swap accounts of a message needing neither compose nor return accounts.
+    fn swap_accounts(
+        types: &LzReceiveTypesAccounts,
+        params: &LzReceiveParams,
+    ) -> Vec<LzAccount> {
+        let accounts = receive_accounts(&crate::ID, &Pubkey::new_unique(), types, params);
+        accounts[CORE_ACCOUNTS_LEN + accounts_for_clear(types, params).len()..].to_vec()
+    }
+
+    #[test]
+    fn leads_with_the_accounts_lz_receive_validates() {
+        let types = types_accounts(vec![]);
+        let types_key = Pubkey::new_unique();
+        let params = receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params()));
+        let accounts = receive_accounts(&crate::ID, &types_key, &types, &params);
+
+        let count = types.count;
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &crate::ID).0;
+        let lz_receive = crate::accounts::LzReceive {
+            payer: Pubkey::default(),
+            count,
+            remote: pda(&[REMOTE_SEED, &count.to_bytes(), &SRC_EID.to_be_bytes()]),
+            receipt: pda(&[RECEIPT_SEED, &count.to_bytes(), &params.guid]),
+            failed_message: pda(&[FAILED_MESSAGE_SEED, &count.to_bytes(), &params.guid]),
+            lz_receive_types_accounts: types_key,
+            endpoint: Pubkey::find_program_address(&[ENDPOINT_SEED], &ENDPOINT_ID).0,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let metas = lz_receive.to_account_metas(None);
+        assert_eq!(metas.len(), CORE_ACCOUNTS_LEN);
+        for (account, meta) in accounts.iter().zip(&metas) {
+            assert_eq!(account.pubkey, meta.pubkey);
+            assert_eq!(account.is_signer, meta.is_signer, "{}", meta.pubkey);
+            assert_eq!(account.is_writable, meta.is_writable, "{}", meta.pubkey);
+        }
+    }
+
+    #[test]
+    fn follows_with_the_clear_accounts_then_the_compose_accounts_if_composed() {
+        let types = types_accounts(vec![]);
+        let params = receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params()));
+        let accounts_for_clear = accounts_for_clear(&types, &params);
+        let accounts = receive_accounts(&crate::ID, &Pubkey::new_unique(), &types, &params);
+        let accounts_after_core = accounts[CORE_ACCOUNTS_LEN..].iter();
+        assert!(accounts_after_core.zip(&accounts_for_clear).all(|(a, b)| a.pubkey == b.pubkey));
+        assert_eq!(
+            accounts.len(),
+            CORE_ACCOUNTS_LEN + accounts_for_clear.len() + crate::settlement::SWAP_ACCOUNTS_LEN
+        );
+
+        let params = receive_params(swap(msg_codec::COMPOSED_TYPE, &swap_params()));
+        let accounts_for_compose = get_accounts_for_send_compose(
+            ENDPOINT_ID,
+            &types.count,
+            &types.count,
+            &params.guid,
+            0,
+            &params.message,
+        );
+        let accounts = receive_accounts(&crate::ID, &Pubkey::new_unique(), &types, &params);
+        // `lz_compose` settles composed swaps, so their swap accounts aren't passed here
+        assert_eq!(
+            accounts.len(),
+            CORE_ACCOUNTS_LEN + accounts_for_clear.len() + accounts_for_compose.len()
+        );
+        let accounts_after_clear = accounts[CORE_ACCOUNTS_LEN + accounts_for_clear.len()..].iter();
+        assert!(accounts_after_clear.zip(&accounts_for_compose).all(|(a, b)| a.pubkey == b.pubkey));
+    }
+
+    #[test]
+    fn passes_the_swap_accounts_settlement_loads() {
+        let types = types_accounts(vec![]);
+        let swap_params = swap_params();
+        let accounts =
+            swap_accounts(&types, &receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params)));
+
+        let count = types.count.to_bytes();
+        let dex_choice = swap_params.dex_choice.to_be_bytes();
+        let (dex_adapter, _) = Pubkey::find_program_address(
+            &[DEX_ADAPTER_SEED, &count, &dex_choice],
+            &crate::ID,
+        );
+        assert_eq!(accounts.len(), crate::settlement::SWAP_ACCOUNTS_LEN);
+        assert_eq!(accounts[0].pubkey.to_bytes(), swap_params.token_in);
+        assert_eq!(accounts[1].pubkey.to_bytes(), swap_params.token_out);
+        assert_eq!(accounts[4].pubkey.to_bytes(), swap_params.recipient);
+        assert_eq!(accounts[7].pubkey, dex_adapter);
+        assert_eq!(accounts[8].pubkey.to_bytes(), swap_params.dex_address);
+        // Only the vaults and the recipient's token accounts are written to
+        let writable: Vec<usize> =
+            (0..accounts.len()).filter(|&i| accounts[i].is_writable).collect();
+        assert_eq!(writable, [2, 3, 5, 6]);
+        assert!(accounts.iter().all(|account| !account.is_signer));
+    }
+
+    #[test]
+    fn stops_after_the_clear_accounts_for_messages_that_dont_decode() {
+        let types = types_accounts(vec![]);
+        let mut message = swap(msg_codec::VANILLA_TYPE, &swap_params());
+        message.truncate(message.len() - 1);
+        assert!(swap_accounts(&types, &receive_params(message)).is_empty());
+        assert!(swap_accounts(&types, &receive_params(vec![])).is_empty());
+    }
+
+    #[test]
+    fn derives_swap_accounts_under_each_mints_token_program() {
+        let swap_params = swap_params();
+        let token_in = Pubkey::new_from_array(swap_params.token_in);
+        let token_out = Pubkey::new_from_array(swap_params.token_out);
+        let types = types_accounts(vec![token_out]);
+        let accounts =
+            swap_accounts(&types, &receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params)));
+
+        let count = types.count;
+        let recipient = Pubkey::new_from_array(swap_params.recipient);
+        let ata = get_associated_token_address_with_program_id;
+        let keys: Vec<Pubkey> = accounts.iter().map(|account| account.pubkey).collect();
+        assert_eq!(keys.len(), crate::settlement::SWAP_ACCOUNTS_LEN);
+        assert_eq!(keys[2], ata(&count, &token_in, &token::ID));
+        assert_eq!(keys[3], ata(&count, &token_out, &token_2022::ID));
+        assert_eq!(keys[5], ata(&recipient, &token_out, &token_2022::ID));
+        assert_eq!(keys[6], ata(&recipient, &token_in, &token::ID));
+        assert_eq!(keys[9..], [token::ID, token_2022::ID]);
+    }
+
+    #[test]
+    fn ends_with_the_transfer_hook_accounts_of_token_in_then_token_out() {
+        let swap_params = swap_params();
+        let hook_accounts = |mint: [u8; 32]| TransferHookAccounts {
+            mint: Pubkey::new_from_array(mint),
+            accounts: vec![
+                LzAccount { pubkey: Pubkey::new_unique(), is_signer: false, is_writable: false },
+                LzAccount { pubkey: Pubkey::new_unique(), is_signer: false, is_writable: true },
+            ],
+        };
+        let token_in_hooks = hook_accounts(swap_params.token_in);
+        let token_out_hooks = hook_accounts(swap_params.token_out);
+        let mut types = types_accounts(vec![]);
+        types.transfer_hook_accounts = vec![token_out_hooks.clone(), token_in_hooks.clone()];
+        let accounts =
+            swap_accounts(&types, &receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params)));
+
+        let keys: Vec<Pubkey> = accounts[crate::settlement::SWAP_ACCOUNTS_LEN..]
+            .iter()
+            .map(|account| account.pubkey)
+            .collect();
+        let expected: Vec<Pubkey> = [token_in_hooks, token_out_hooks]
+            .iter()
+            .flat_map(|hooks| hooks.accounts.iter().map(|account| account.pubkey))
+            .collect();
+        assert_eq!(keys, expected);
+        assert!(accounts[crate::settlement::SWAP_ACCOUNTS_LEN + 1].is_writable);
+    }
+
+    #[test]
+    fn derives_intermediate_vaults_under_their_mints_token_program() {
+        let hop = Pubkey::new_unique();
+        let types = types_accounts(vec![hop]);
+        let mut swap_params = swap_params();
+        swap_params.path = vec![swap_params.token_in, hop.to_bytes(), swap_params.token_out];
+        let accounts =
+            swap_accounts(&types, &receive_params(swap(msg_codec::VANILLA_TYPE, &swap_params)));
+
+        let vault =
+            get_associated_token_address_with_program_id(&types.count, &hop, &token_2022::ID);
+        assert_eq!(accounts.len(), crate::settlement::SWAP_ACCOUNTS_LEN + 1);
+        assert_eq!(accounts[crate::settlement::SWAP_ACCOUNTS_LEN].pubkey, vault);
+    }
+}
 
 
 
@@ -1,9 +1,65 @@
+pub mod accept_admin;
+pub mod burn;
+pub mod clear;
+pub mod close_receipt;
+pub mod deposit;
+pub mod discard_message;
+pub mod init_count;
+pub mod init_vault;
+pub mod lz_compose;
+pub mod lz_compose_types;
 pub mod lz_receive;
 pub mod lz_receive_types;
+pub mod next_nonce;
+pub mod nilify;
+pub mod pause;
+pub mod propose_admin;
 pub mod quote;
+pub mod remove_remote;
+pub mod renounce_admin;
+pub mod retry_message;
+pub mod send;
+pub mod set_dex_adapter;
+pub mod set_pauser;
 pub mod set_remote;
+pub mod set_remote_enabled;
+pub mod set_remote_non_blocking;
+pub mod set_remote_ordered;
+pub mod set_return_accounts;
+pub mod set_transfer_hook_accounts;
+pub mod skip;
+pub mod unpause;
+pub mod withdraw;
 
+pub use accept_admin::*;
+pub use burn::*;
+pub use clear::*;
+pub use close_receipt::*;
+pub use deposit::*;
+pub use discard_message::*;
+pub use init_count::*;
+pub use init_vault::*;
+pub use lz_compose::*;
+pub use lz_compose_types::*;
 pub use lz_receive::*;
 pub use lz_receive_types::*;
+pub use next_nonce::*;
+pub use nilify::*;
+pub use pause::*;
+pub use propose_admin::*;
 pub use quote::*;
+pub use remove_remote::*;
+pub use renounce_admin::*;
+pub use retry_message::*;
+pub use send::*;
+pub use set_dex_adapter::*;
+pub use set_pauser::*;
 pub use set_remote::*;
+pub use set_remote_enabled::*;
+pub use set_remote_non_blocking::*;
+pub use set_remote_ordered::*;
+pub use set_return_accounts::*;
+pub use set_transfer_hook_accounts::*;
+pub use skip::*;
+pub use unpause::*;
+pub use withdraw::*;
@@ -0,0 +1,32 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: NextNonceParams)]
+pub struct NextNonce<'info> {
+    #[account(
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.src_eid.to_be_bytes()],
+        bump = remote.bump
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl NextNonce<'_> {
+    /// Returns the next inbound nonce the OApp will accept from `sender`, or 0 when
+    /// delivery is unordered.
+    pub fn apply(ctx: &Context<NextNonce>, params: &NextNonceParams) -> Result<u64> {
+        let remote = &ctx.accounts.remote;
+        if !remote.ordered || remote.address != params.sender {
+            return Ok(0);
+        }
+        Ok(remote.inbound_nonce + 1)
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct NextNonceParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+}
@@ -0,0 +1,51 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    cpi::accounts::Nilify as EndpointNilify,
+    instructions::NilifyParams as EndpointNilifyParams,
+    ConstructCPIContext, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+pub struct Nilify<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl<'info> Nilify<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Nilify<'info>>,
+        params: &NilifyParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        let cpi_ctx = EndpointNilify::construct_context(ENDPOINT_ID, ctx.remaining_accounts)?;
+        oapp::endpoint::cpi::nilify(
+            cpi_ctx.with_signer(&[seeds]),
+            EndpointNilifyParams {
+                receiver: ctx.accounts.count.key(),
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+                payload_hash: params.payload_hash,
+            },
+        )?;
+        emit!(NonceNilified {
+            count: ctx.accounts.count.key(),
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct NilifyParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub payload_hash: [u8; 32],
+}
@@ -0,0 +1,21 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    #[account(
+        constraint = signer.key() == count.admin || signer.key() == count.pauser
+            @ CounterError::UnauthorizedPauser
+    )]
+    pub signer: Signer<'info>,
+    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl Pause<'_> {
+    pub fn apply(ctx: &mut Context<Pause>) -> Result<()> {
+        ctx.accounts.count.paused = true;
+        emit!(PausedSet { count: ctx.accounts.count.key(), paused: true });
+        Ok(())
+    }
+}
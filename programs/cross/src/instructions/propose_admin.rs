@@ -0,0 +1,27 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl ProposeAdmin<'_> {
+    pub fn apply(ctx: &mut Context<ProposeAdmin>, params: &ProposeAdminParams) -> Result<()> {
+        ctx.accounts.count.pending_admin = Some(params.pending_admin);
+        emit!(AdminProposed {
+            count: ctx.accounts.count.key(),
+            admin: ctx.accounts.count.admin,
+            pending_admin: params.pending_admin,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ProposeAdminParams {
+    pub pending_admin: Pubkey,
+}
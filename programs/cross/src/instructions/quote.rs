@@ -10,20 +10,32 @@ use oapp::endpoint::{
 pub struct Quote<'info> {
     #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
     pub count: Account<'info, Count>,
+    #[account(
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = remote.outbound_enabled @ CounterError::RemoteDisabled
+    )]
+    pub remote: Account<'info, Remote>,
     #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
     pub endpoint: Account<'info, EndpointSettings>, // Ensure EndpointSettings implements AnchorSerialize/Deserialize
 }
 
 impl<'info> Quote<'info> {
     pub fn apply(ctx: &Context<Quote>, params: &QuoteParams) -> Result<MessagingFee> {
-        // Encode the message type
-        let message = msg_codec::encode(params.msg_type, ctx.accounts.endpoint.eid);
+        // Encode the swap message
+        let message = msg_codec::encode_swap(
+            params.msg_type,
+            ctx.accounts.endpoint.eid,
+            &params.swap_params,
+            params.return_params.as_ref(),
+        )?;
 
         // Prepare the quote parameters
         let quote_params = EndpointQuoteParams {
             sender: ctx.accounts.count.key(),
             dst_eid: params.dst_eid,
-            receiver: params.receiver,
+            // Quote for the peer `Send::apply` sends to, so the fee matches
+            receiver: ctx.accounts.remote.address,
             message,
             pay_in_lz_token: params.pay_in_lz_token,
             options: params.options.clone(),
@@ -37,8 +49,9 @@ impl<'info> Quote<'info> {
 #[derive(Clone, AnchorSerialize, AnchorDeserialize)]
 pub struct QuoteParams {
     pub dst_eid: u32,
-    pub receiver: [u8; 32],
     pub msg_type: u8,
+    pub swap_params: msg_codec::SwapParams,
+    pub return_params: Option<msg_codec::ReturnParams>, // Required for ABA types only
     pub options: Vec<u8>,
     pub pay_in_lz_token: bool,
 }
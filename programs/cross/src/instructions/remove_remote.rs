@@ -0,0 +1,34 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: RemoveRemoteParams)]
+pub struct RemoveRemote<'info> {
+    #[account(mut, address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        close = admin,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl RemoveRemote<'_> {
+    pub fn apply(ctx: &mut Context<RemoveRemote>, params: &RemoveRemoteParams) -> Result<()> {
+        emit!(RemoteRemoved {
+            count: ctx.accounts.count.key(),
+            eid: params.dst_eid,
+            address: ctx.accounts.remote.address,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct RemoveRemoteParams {
+    pub dst_eid: u32,
+}
@@ -0,0 +1,34 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RenounceAdmin<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    // Only the admin can unpause, so renouncing while paused would freeze Count for good
+    #[account(
+        mut,
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
+    pub count: Account<'info, Count>,
+}
+
+impl RenounceAdmin<'_> {
+    /// Permanently gives up admin rights, no admin-gated instruction can be called afterwards.
+    /// The pauser goes too, as nobody could undo a pause.
+    pub fn apply(ctx: &mut Context<RenounceAdmin>) -> Result<()> {
+        let old_admin = ctx.accounts.count.admin;
+        ctx.accounts.count.admin = Pubkey::default();
+        ctx.accounts.count.pending_admin = None;
+        ctx.accounts.count.pauser = Pubkey::default();
+        emit!(AdminChanged {
+            count: ctx.accounts.count.key(),
+            old_admin,
+            new_admin: Pubkey::default(),
+        });
+        emit!(PauserSet { count: ctx.accounts.count.key(), pauser: Pubkey::default() });
+        Ok(())
+    }
+}
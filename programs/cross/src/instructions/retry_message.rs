@@ -0,0 +1,104 @@
+use crate::settlement::{InboundMessage, Settlement};
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use oapp::endpoint::{state::EndpointSettings, ENDPOINT_SEED, ID as ENDPOINT_ID};
+
+/// The remaining accounts are those following the endpoint accounts of `LzReceive`: the swap
+/// accounts, the vaults for intermediate hops, the return leg accounts of ABA swaps, then the
+/// adapter accounts.
+#[derive(Accounts)]
+#[instruction(params: RetryMessageParams)]
+pub struct RetryMessage<'info> {
+    /// Anyone may retry, pays for the recipient's token accounts if missing
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
+    pub count: Account<'info, Count>,
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [FAILED_MESSAGE_SEED, &count.key().to_bytes(), &params.guid],
+        bump = failed_message.bump
+    )]
+    pub failed_message: Box<Account<'info, FailedMessage>>,
+    /// CHECK: paid the failed message's rent, refunded on success
+    #[account(mut, address = failed_message.payer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &failed_message.src_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = remote.inbound_enabled @ CounterError::RemoteDisabled
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(
+        mut,
+        seeds = [RECEIPT_SEED, &count.key().to_bytes(), &params.guid],
+        bump = receipt.bump
+    )]
+    pub receipt: Box<Account<'info, MessageReceipt>>,
+
+    #[account(seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Box<Account<'info, LzReceiveTypesAccounts>>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Box<Account<'info, EndpointSettings>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RetryMessage<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, RetryMessage<'info>>,
+        params: &RetryMessageParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+
+        let failed_message = &ctx.accounts.failed_message;
+        let payload = failed_message.message.clone();
+        let message = InboundMessage {
+            guid: params.guid,
+            src_eid: failed_message.src_eid,
+            sender: failed_message.sender,
+            nonce: failed_message.nonce,
+            message: &payload,
+        };
+
+        // A retry that fails again reverts, leaving the message stored
+        let mut settlement = ctx.accounts.settlement(ctx.program_id);
+        let plan = settlement.check(&message, ctx.remaining_accounts)?;
+        settlement.execute(&message, plan, seeds)?;
+        // Counted as failed when stored, `execute` counted it again by its outcome. A remote
+        // removed and set again since starts its statistics over, so don't go below zero.
+        let remote = &mut ctx.accounts.remote;
+        remote.swaps_failed = remote.swaps_failed.saturating_sub(1);
+
+        emit!(MessageRetried { count: ctx.accounts.count.key(), guid: params.guid });
+        Ok(())
+    }
+
+    fn settlement<'a>(&'a mut self, program_id: &'a Pubkey) -> Settlement<'a, 'info> {
+        Settlement {
+            program_id,
+            payer: &self.payer,
+            count: &self.count,
+            remote: &mut self.remote,
+            receipt: &mut self.receipt,
+            lz_receive_types_accounts: &self.lz_receive_types_accounts,
+            endpoint: &self.endpoint,
+            associated_token_program: &self.associated_token_program,
+            system_program: &self.system_program,
+        }
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct RetryMessageParams {
+    pub guid: [u8; 32],
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    instructions::SendParams as EndpointSendParams, state::EndpointSettings, MessagingReceipt,
+    ENDPOINT_SEED, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: SendParams)]
+pub struct Send<'info> {
+    /// Remotes pay these swaps out of Count's vaults, so only the admin may send them
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = remote.outbound_enabled @ CounterError::RemoteDisabled
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
+    pub count: Account<'info, Count>,
+    #[account(seeds = [ENDPOINT_SEED], bump = endpoint.bump, seeds::program = ENDPOINT_ID)]
+    pub endpoint: Account<'info, EndpointSettings>,
+}
+
+impl Send<'_> {
+    pub fn apply(ctx: &mut Context<Send>, params: &SendParams) -> Result<MessagingReceipt> {
+        // Encode the message exactly as `Quote::apply` does so the quoted fee holds
+        let message = msg_codec::encode_swap(
+            params.msg_type,
+            ctx.accounts.endpoint.eid,
+            &params.swap_params,
+            params.return_params.as_ref(),
+        )?;
+
+        let send_params = EndpointSendParams {
+            dst_eid: params.dst_eid,
+            receiver: ctx.accounts.remote.address,
+            message,
+            options: params.options.clone(),
+            native_fee: params.fee.native_fee,
+            lz_token_fee: params.fee.lz_token_fee,
+        };
+
+        // The payer in `remaining_accounts` covers the fee, the Count PDA signs as sender
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        let receipt = oapp::endpoint_cpi::send(
+            ENDPOINT_ID,
+            ctx.accounts.count.key(),
+            ctx.remaining_accounts,
+            seeds,
+            send_params,
+        )?;
+
+        // Never charge the caller more than the fee they agreed to
+        require!(
+            receipt.fee.native_fee <= params.fee.native_fee
+                && receipt.fee.lz_token_fee <= params.fee.lz_token_fee,
+            CounterError::FeeExceedsLimit
+        );
+
+        emit!(MessageSent {
+            count: ctx.accounts.count.key(),
+            dst_eid: params.dst_eid,
+            guid: receipt.guid,
+            nonce: receipt.nonce,
+            msg_type: params.msg_type,
+            native_fee: receipt.fee.native_fee,
+            lz_token_fee: receipt.fee.lz_token_fee,
+        });
+
+        Ok(receipt)
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SendParams {
+    pub dst_eid: u32,
+    pub msg_type: u8,
+    pub swap_params: msg_codec::SwapParams,
+    pub return_params: Option<msg_codec::ReturnParams>, // Required for ABA types only
+    pub options: Vec<u8>,
+    pub fee: MessagingFee,
+}
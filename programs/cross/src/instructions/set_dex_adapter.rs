@@ -0,0 +1,49 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetDexAdapterParams)]
+pub struct SetDexAdapter<'info> {
+    #[account(mut, address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = DexAdapter::SIZE,
+        seeds = [DEX_ADAPTER_SEED, &count.key().to_bytes(), &params.dex_choice.to_be_bytes()],
+        bump
+    )]
+    pub dex_adapter: Account<'info, DexAdapter>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetDexAdapter<'_> {
+    pub fn apply(ctx: &mut Context<SetDexAdapter>, params: &SetDexAdapterParams) -> Result<()> {
+        require!(params.kind == dex::GENERIC_ADAPTER_KIND, CounterError::UnsupportedAdapterKind);
+
+        ctx.accounts.dex_adapter.dex_choice = params.dex_choice;
+        ctx.accounts.dex_adapter.program_id = params.program_id;
+        ctx.accounts.dex_adapter.kind = params.kind;
+        ctx.accounts.dex_adapter.enabled = params.enabled;
+        ctx.accounts.dex_adapter.bump = ctx.bumps.dex_adapter;
+
+        emit!(DexAdapterSet {
+            count: ctx.accounts.count.key(),
+            dex_choice: params.dex_choice,
+            program_id: params.program_id,
+            kind: params.kind,
+            enabled: params.enabled,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetDexAdapterParams {
+    pub dex_choice: u8,
+    pub program_id: Pubkey,
+    pub kind: u8,
+    pub enabled: bool,
+}
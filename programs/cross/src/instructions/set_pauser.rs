@@ -0,0 +1,23 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPauser<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl SetPauser<'_> {
+    pub fn apply(ctx: &mut Context<SetPauser>, params: &SetPauserParams) -> Result<()> {
+        ctx.accounts.count.pauser = params.pauser;
+        emit!(PauserSet { count: ctx.accounts.count.key(), pauser: params.pauser });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetPauserParams {
+    pub pauser: Pubkey,
+}
@@ -14,15 +14,32 @@ pub struct SetRemote<'info> {
         bump
     )]
     pub remote: Account<'info, Remote>,
-    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    #[account(
+        seeds = [COUNT_SEED, &count.id.to_be_bytes()],
+        bump = count.bump,
+        constraint = !count.paused @ CounterError::Paused
+    )]
     pub count: Account<'info, Count>,
     pub system_program: Program<'info, System>,
 }
 
 impl SetRemote<'_> {
     pub fn apply(ctx: &mut Context<SetRemote>, params: &SetRemoteParams) -> Result<()> {
+        require!(params.remote != [0; 32], CounterError::ZeroRemoteAddress);
+
+        // Set remotes never hold a zero address, so one means the account was just created,
+        // and new peers start enabled
+        if ctx.accounts.remote.address == [0; 32] {
+            ctx.accounts.remote.inbound_enabled = true;
+            ctx.accounts.remote.outbound_enabled = true;
+        }
         ctx.accounts.remote.address = params.remote;
         ctx.accounts.remote.bump = ctx.bumps.remote;
+        emit!(RemoteSet {
+            count: ctx.accounts.count.key(),
+            eid: params.dst_eid,
+            address: params.remote,
+        });
         Ok(())
     }
 }
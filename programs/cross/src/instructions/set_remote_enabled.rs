@@ -0,0 +1,41 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetRemoteEnabledParams)]
+pub struct SetRemoteEnabled<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl SetRemoteEnabled<'_> {
+    pub fn apply(
+        ctx: &mut Context<SetRemoteEnabled>,
+        params: &SetRemoteEnabledParams,
+    ) -> Result<()> {
+        ctx.accounts.remote.inbound_enabled = params.inbound_enabled;
+        ctx.accounts.remote.outbound_enabled = params.outbound_enabled;
+        emit!(RemoteEnabledSet {
+            count: ctx.accounts.count.key(),
+            eid: params.dst_eid,
+            inbound_enabled: params.inbound_enabled,
+            outbound_enabled: params.outbound_enabled,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetRemoteEnabledParams {
+    pub dst_eid: u32,
+    pub inbound_enabled: bool,
+    pub outbound_enabled: bool,
+}
@@ -0,0 +1,38 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetRemoteNonBlockingParams)]
+pub struct SetRemoteNonBlocking<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl SetRemoteNonBlocking<'_> {
+    pub fn apply(
+        ctx: &mut Context<SetRemoteNonBlocking>,
+        params: &SetRemoteNonBlockingParams,
+    ) -> Result<()> {
+        ctx.accounts.remote.non_blocking = params.non_blocking;
+        emit!(RemoteNonBlockingSet {
+            count: ctx.accounts.count.key(),
+            eid: params.dst_eid,
+            non_blocking: params.non_blocking,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetRemoteNonBlockingParams {
+    pub dst_eid: u32,
+    pub non_blocking: bool,
+}
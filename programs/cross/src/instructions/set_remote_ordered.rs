@@ -0,0 +1,41 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: SetRemoteOrderedParams)]
+pub struct SetRemoteOrdered<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.dst_eid.to_be_bytes()],
+        bump = remote.bump
+    )]
+    pub remote: Account<'info, Remote>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl SetRemoteOrdered<'_> {
+    pub fn apply(
+        ctx: &mut Context<SetRemoteOrdered>,
+        params: &SetRemoteOrderedParams,
+    ) -> Result<()> {
+        ctx.accounts.remote.ordered = params.ordered;
+        ctx.accounts.remote.inbound_nonce = params.inbound_nonce;
+        emit!(RemoteOrderedSet {
+            count: ctx.accounts.count.key(),
+            eid: params.dst_eid,
+            ordered: params.ordered,
+            inbound_nonce: params.inbound_nonce,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetRemoteOrderedParams {
+    pub dst_eid: u32,
+    pub ordered: bool,
+    pub inbound_nonce: u64, // Last nonce already processed, the next accepted is one above
+}
@@ -0,0 +1,56 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint_cpi::LzAccount;
+
+#[derive(Accounts)]
+pub struct SetReturnAccounts<'info> {
+    #[account(mut, address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(mut, seeds = [LZ_COMPOSE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_compose_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetReturnAccounts<'_> {
+    /// Sets the endpoint `send` accounts ABA messages from `eid` return through, an empty list
+    /// removes them. They depend on the send library config, so they are built off-chain.
+    pub fn apply(
+        ctx: &mut Context<SetReturnAccounts>,
+        params: &SetReturnAccountsParams,
+    ) -> Result<()> {
+        for types_accounts in [
+            &mut ctx.accounts.lz_receive_types_accounts,
+            &mut ctx.accounts.lz_compose_types_accounts,
+        ] {
+            types_accounts.return_accounts.retain(|r| r.eid != params.eid);
+            if !params.accounts.is_empty() {
+                types_accounts.return_accounts.push(ReturnAccounts {
+                    eid: params.eid,
+                    accounts: params.accounts.clone(),
+                });
+            }
+            LzReceiveTypesAccounts::grow_to_fit(
+                types_accounts,
+                &ctx.accounts.admin.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+
+        emit!(ReturnAccountsSet {
+            count: ctx.accounts.count.key(),
+            eid: params.eid,
+            accounts_len: params.accounts.len() as u32,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetReturnAccountsParams {
+    pub eid: u32,
+    pub accounts: Vec<LzAccount>,
+}
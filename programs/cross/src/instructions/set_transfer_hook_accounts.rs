@@ -0,0 +1,64 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use oapp::endpoint_cpi::LzAccount;
+
+#[derive(Accounts)]
+pub struct SetTransferHookAccounts<'info> {
+    #[account(mut, address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [LZ_RECEIVE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_receive_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(mut, seeds = [LZ_COMPOSE_TYPES_SEED, &count.key().to_bytes()], bump)]
+    pub lz_compose_types_accounts: Account<'info, LzReceiveTypesAccounts>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SetTransferHookAccounts<'_> {
+    /// Sets the extra accounts transfers of a transfer hook mint pass to the hook, an empty
+    /// list removes them. The hook's validation account may derive them from anything, so
+    /// they are resolved off-chain with the transfer hook interface's helpers.
+    pub fn apply(
+        ctx: &mut Context<SetTransferHookAccounts>,
+        params: &SetTransferHookAccountsParams,
+    ) -> Result<()> {
+        let mint = ctx.accounts.mint.key();
+        require!(
+            params.accounts.is_empty()
+                || dex::has_transfer_hook(&ctx.accounts.mint.to_account_info())?,
+            CounterError::NoTransferHook
+        );
+        for types_accounts in [
+            &mut ctx.accounts.lz_receive_types_accounts,
+            &mut ctx.accounts.lz_compose_types_accounts,
+        ] {
+            types_accounts.transfer_hook_accounts.retain(|h| h.mint != mint);
+            if !params.accounts.is_empty() {
+                types_accounts.transfer_hook_accounts.push(TransferHookAccounts {
+                    mint,
+                    accounts: params.accounts.clone(),
+                });
+            }
+            LzReceiveTypesAccounts::grow_to_fit(
+                types_accounts,
+                &ctx.accounts.admin.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+
+        emit!(TransferHookAccountsSet {
+            count: ctx.accounts.count.key(),
+            mint,
+            accounts_len: params.accounts.len() as u32,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SetTransferHookAccountsParams {
+    pub accounts: Vec<LzAccount>,
+}
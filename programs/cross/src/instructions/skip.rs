@@ -0,0 +1,57 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use oapp::endpoint::{
+    cpi::accounts::Skip as EndpointSkip, instructions::SkipParams as EndpointSkipParams,
+    ConstructCPIContext, ID as ENDPOINT_ID,
+};
+
+#[derive(Accounts)]
+#[instruction(params: SkipParams)]
+pub struct Skip<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(
+        mut,
+        seeds = [REMOTE_SEED, &count.key().to_bytes(), &params.src_eid.to_be_bytes()],
+        bump = remote.bump,
+        constraint = params.sender == remote.address
+    )]
+    pub remote: Account<'info, Remote>,
+}
+
+impl<'info> Skip<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Skip<'info>>,
+        params: &SkipParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        let cpi_ctx = EndpointSkip::construct_context(ENDPOINT_ID, ctx.remaining_accounts)?;
+        oapp::endpoint::cpi::skip(
+            cpi_ctx.with_signer(&[seeds]),
+            EndpointSkipParams {
+                receiver: ctx.accounts.count.key(),
+                src_eid: params.src_eid,
+                sender: params.sender,
+                nonce: params.nonce,
+            },
+        )?;
+        ctx.accounts.remote.consume_nonce(params.nonce)?;
+        emit!(NonceSkipped {
+            count: ctx.accounts.count.key(),
+            src_eid: params.src_eid,
+            sender: params.sender,
+            nonce: params.nonce,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct SkipParams {
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+}
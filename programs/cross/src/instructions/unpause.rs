@@ -0,0 +1,18 @@
+use crate::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+}
+
+impl Unpause<'_> {
+    pub fn apply(ctx: &mut Context<Unpause>) -> Result<()> {
+        ctx.accounts.count.paused = false;
+        emit!(PausedSet { count: ctx.accounts.count.key(), paused: false });
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// Transfer hook mints take the hook's extra accounts as remaining accounts.
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(address = count.admin)]
+    pub admin: Signer<'info>,
+    #[account(seeds = [COUNT_SEED, &count.id.to_be_bytes()], bump = count.bump)]
+    pub count: Account<'info, Count>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, associated_token::mint = mint,
+        associated_token::authority = count,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Withdraw<'info> {
+    pub fn apply(
+        ctx: &mut Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        params: &WithdrawParams,
+    ) -> Result<()> {
+        let seeds: &[&[u8]] =
+            &[COUNT_SEED, &ctx.accounts.count.id.to_be_bytes(), &[ctx.accounts.count.bump]];
+        dex::transfer_checked(
+            dex::TransferAccounts {
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                from: &ctx.accounts.vault.to_account_info(),
+                mint: &ctx.accounts.mint.to_account_info(),
+                to: &ctx.accounts.destination.to_account_info(),
+                authority: &ctx.accounts.count.to_account_info(),
+                hook_accounts: ctx.remaining_accounts,
+            },
+            params.amount,
+            ctx.accounts.mint.decimals,
+            &[seeds],
+        )?;
+        emit!(Withdrawn {
+            count: ctx.accounts.count.key(),
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            amount: params.amount,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct WithdrawParams {
+    pub amount: u64,
+}
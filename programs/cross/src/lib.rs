@@ -1,29 +1,35 @@
 
+mod dex;
 mod errors;
+mod events;
 mod instructions;
 mod msg_codec;
+mod settlement;
 mod state;
 
-use msg_codec::{msg_type, src_eid};
 use crate::instructions::quote::Quote;
 use anchor_lang::prelude::*;
 use errors::*;
+use events::*;
 use instructions::*;
 // use msg_codec::*;
 use state::*;
 #[allow(unused_imports)]
 use oapp::{
-    endpoint::{MessagingFee, ID as ENDPOINT_ID},
+    endpoint::{MessagingFee, MessagingReceipt, ID as ENDPOINT_ID},
     endpoint_cpi::LzAccount,
-    /*ComposeParams,*/LzReceiveParams,
+    LzComposeParams, LzReceiveParams,
 };
 
 
 
 const LZ_RECEIVE_TYPES_SEED: &[u8] = b"LzReceiveTypes";
-// const LZ_COMPOSE_TYPES_SEED: &[u8] = b"LzComposeTypes";
+const LZ_COMPOSE_TYPES_SEED: &[u8] = b"LzComposeTypes";
 const COUNT_SEED: &[u8] = b"Count";
 const REMOTE_SEED: &[u8] = b"Remote";
+const DEX_ADAPTER_SEED: &[u8] = b"DexAdapter";
+const RECEIPT_SEED: &[u8] = b"Receipt";
+const FAILED_MESSAGE_SEED: &[u8] = b"FailedMessage";
 
 declare_id!("7BSfbpNXwBWyaKErQk5ReWm2zKVcxWjiXQRW2RRJmARf");  // Replace with your actual program ID
 
@@ -31,32 +37,204 @@ declare_id!("7BSfbpNXwBWyaKErQk5ReWm2zKVcxWjiXQRW2RRJmARf");  // Replace with yo
 pub mod lzreceiver {
     use super::*;
 
+    /// Creates the Count PDA and registers it as an OApp with the LayerZero endpoint, upgrade
+    /// authority only.
+    pub fn init_count(mut ctx: Context<InitCount>, params: InitCountParams) -> Result<()> {
+        InitCount::apply(&mut ctx, &params)
+    }
+
+    /// Proposes a new admin, who takes over once they call `accept_admin`.
+    pub fn propose_admin(
+        mut ctx: Context<ProposeAdmin>,
+        params: ProposeAdminParams,
+    ) -> Result<()> {
+        ProposeAdmin::apply(&mut ctx, &params)
+    }
+
+    /// Completes an admin transfer, signed by the proposed admin.
+    pub fn accept_admin(mut ctx: Context<AcceptAdmin>) -> Result<()> {
+        AcceptAdmin::apply(&mut ctx)
+    }
+
+    /// Gives up admin rights for good, along with the pauser. Refused while paused.
+    pub fn renounce_admin(mut ctx: Context<RenounceAdmin>) -> Result<()> {
+        RenounceAdmin::apply(&mut ctx)
+    }
+
+    /// Sets the account allowed to pause alongside the admin.
+    pub fn set_pauser(mut ctx: Context<SetPauser>, params: SetPauserParams) -> Result<()> {
+        SetPauser::apply(&mut ctx, &params)
+    }
+
+    /// Pauses receiving, sending and setting remotes, signed by the admin or pauser.
+    pub fn pause(mut ctx: Context<Pause>) -> Result<()> {
+        Pause::apply(&mut ctx)
+    }
+
+    /// Lifts a pause, admin only.
+    pub fn unpause(mut ctx: Context<Unpause>) -> Result<()> {
+        Unpause::apply(&mut ctx)
+    }
+
     /// Sets the remote sender address for LayerZero verification.
     pub fn set_remote(mut ctx: Context<SetRemote>, params: SetRemoteParams) -> Result<()> {
         SetRemote::apply(&mut ctx, &params)
     }
 
+    /// Closes a remote, revoking its trust and refunding rent to the admin.
+    pub fn remove_remote(mut ctx: Context<RemoveRemote>, params: RemoveRemoteParams) -> Result<()> {
+        RemoveRemote::apply(&mut ctx, &params)
+    }
+
+    /// Enables or disables inbound and outbound traffic for a single remote.
+    pub fn set_remote_enabled(
+        mut ctx: Context<SetRemoteEnabled>,
+        params: SetRemoteEnabledParams,
+    ) -> Result<()> {
+        SetRemoteEnabled::apply(&mut ctx, &params)
+    }
+
+    /// Registers, updates or disables the DEX adapter used for a `dex_choice`.
+    pub fn set_dex_adapter(
+        mut ctx: Context<SetDexAdapter>,
+        params: SetDexAdapterParams,
+    ) -> Result<()> {
+        SetDexAdapter::apply(&mut ctx, &params)
+    }
+
+    /// Sets the endpoint accounts ABA swaps from a remote send their result back through.
+    pub fn set_return_accounts(
+        mut ctx: Context<SetReturnAccounts>,
+        params: SetReturnAccountsParams,
+    ) -> Result<()> {
+        SetReturnAccounts::apply(&mut ctx, &params)
+    }
+
+    /// Sets the extra accounts `lz_receive` passes to the transfer hook of a vault mint.
+    pub fn set_transfer_hook_accounts(
+        mut ctx: Context<SetTransferHookAccounts>,
+        params: SetTransferHookAccountsParams,
+    ) -> Result<()> {
+        SetTransferHookAccounts::apply(&mut ctx, &params)
+    }
+
+    /// Switches a remote between ordered and unordered inbound delivery.
+    pub fn set_remote_ordered(
+        mut ctx: Context<SetRemoteOrdered>,
+        params: SetRemoteOrderedParams,
+    ) -> Result<()> {
+        SetRemoteOrdered::apply(&mut ctx, &params)
+    }
+
+    /// Switches a remote between reverting on failed messages and storing them for a retry.
+    pub fn set_remote_non_blocking(
+        mut ctx: Context<SetRemoteNonBlocking>,
+        params: SetRemoteNonBlockingParams,
+    ) -> Result<()> {
+        SetRemoteNonBlocking::apply(&mut ctx, &params)
+    }
+
+    /// Returns the next inbound nonce expected from a remote, 0 when unordered.
+    pub fn next_nonce(ctx: Context<NextNonce>, params: NextNonceParams) -> Result<u64> {
+        NextNonce::apply(&ctx, &params)
+    }
+
+    /// Creates the Count PDA's vault for a mint, holding liquidity for received swaps.
+    pub fn init_vault(mut ctx: Context<InitVault>) -> Result<()> {
+        InitVault::apply(&mut ctx)
+    }
+
+    /// Deposits tokens into a vault.
+    pub fn deposit<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
+        params: DepositParams,
+    ) -> Result<()> {
+        Deposit::apply(&mut ctx, &params)
+    }
+
+    /// Withdraws tokens from a vault, admin only.
+    pub fn withdraw<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        params: WithdrawParams,
+    ) -> Result<()> {
+        Withdraw::apply(&mut ctx, &params)
+    }
+
     /// Estimates the messaging fee for sending a LayerZero message.
     pub fn quote(ctx: Context<Quote>, params: QuoteParams) -> Result<MessagingFee> {
         Quote::apply(&ctx, &params)
     }
 
-    // /// Receives a LayerZero message and processes swap instructions.
-    // pub fn lz_receive(mut ctx: Context<LzReceive>, params: LzReceiveParams) -> Result<()> {
-    //     LzReceive::apply(&mut ctx, &params)
-    // }
-    
-    pub fn lz_receive(mut ctx: Context<LzReceive>, params: LzReceiveParams) -> Result<()> {
-        // let message = params.payload.as_slice();
-        let message = params.message.as_slice();
-        let message_type = msg_type(message);
-        let source_eid = src_eid(message);
-    
-        msg!("Received message type: {}", message_type);
-        msg!("Source EID: {}", source_eid);
-    
+    /// Sends a LayerZero message to the remote peer, admin only, paying the fee from the caller.
+    pub fn send(mut ctx: Context<Send>, params: SendParams) -> Result<MessagingReceipt> {
+        Send::apply(&mut ctx, &params)
+    }
+
+    /// Receives a LayerZero message and executes the swap it carries, composed swaps are queued
+    /// for `lz_compose`.
+    pub fn lz_receive<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, LzReceive<'info>>,
+        params: LzReceiveParams,
+    ) -> Result<()> {
         LzReceive::apply(&mut ctx, &params)
     }
+
+    /// Closes a message receipt, admin only, refunding its rent to the executor that paid it.
+    pub fn close_receipt(mut ctx: Context<CloseReceipt>, params: CloseReceiptParams) -> Result<()> {
+        CloseReceipt::apply(&mut ctx, &params)
+    }
+
+    /// Re-executes a message stored by a non-blocking remote, callable by anyone.
+    pub fn retry_message<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, RetryMessage<'info>>,
+        params: RetryMessageParams,
+    ) -> Result<()> {
+        RetryMessage::apply(&mut ctx, &params)
+    }
+
+    /// Drops a stored failed message without executing it, admin only.
+    pub fn discard_message(
+        mut ctx: Context<DiscardMessage>,
+        params: DiscardMessageParams,
+    ) -> Result<()> {
+        DiscardMessage::apply(&mut ctx, &params)
+    }
+
+    // `skip`, `clear`, `nilify` and `burn` take the accounts of the endpoint instruction of the
+    // same name as remaining accounts, Count signs for them
+
+    /// Skips an inbound nonce that has not been verified yet, admin only.
+    pub fn skip<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Skip<'info>>,
+        params: SkipParams,
+    ) -> Result<()> {
+        Skip::apply(&mut ctx, &params)
+    }
+
+    /// Clears a verified message without executing it, admin only.
+    pub fn clear<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Clear<'info>>,
+        params: ClearParams,
+    ) -> Result<()> {
+        Clear::apply(&mut ctx, &params)
+    }
+
+    /// Marks a verified message as nil until it is verified again, admin only.
+    pub fn nilify<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Nilify<'info>>,
+        params: NilifyParams,
+    ) -> Result<()> {
+        Nilify::apply(&mut ctx, &params)
+    }
+
+    /// Permanently drops a verified message so it can never be delivered, admin only.
+    pub fn burn<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, Burn<'info>>,
+        params: BurnParams,
+    ) -> Result<()> {
+        Burn::apply(&mut ctx, &params)
+    }
+
     /// Returns the required accounts for the `LzReceive` instruction.
     pub fn lz_receive_types(
         ctx: Context<LzReceiveTypes>,
@@ -64,6 +242,22 @@ pub mod lzreceiver {
     ) -> Result<Vec<LzAccount>> {
         LzReceiveTypes::apply(&ctx, &params)
     }
+
+    /// Clears a composed message queued by `lz_receive` and settles its swap.
+    pub fn lz_compose<'info>(
+        mut ctx: Context<'_, '_, '_, 'info, LzCompose<'info>>,
+        params: LzComposeParams,
+    ) -> Result<()> {
+        LzCompose::apply(&mut ctx, &params)
+    }
+
+    /// Returns the required accounts for the `LzCompose` instruction.
+    pub fn lz_compose_types(
+        ctx: Context<LzComposeTypes>,
+        params: LzComposeParams,
+    ) -> Result<Vec<LzAccount>> {
+        LzComposeTypes::apply(&ctx, &params)
+    }
 }
 
 
@@ -1,25 +1,426 @@
+//! Wire format for swap messages exchanged with remote OApps.
+//!
+//! Every message is a fixed 10-byte header followed by the body:
+//!
+//! | offset | size | field                          |
+//! |--------|------|--------------------------------|
+//! | 0      | 1    | version (`VERSION`)            |
+//! | 1      | 1    | msg_type (`VANILLA_TYPE`, ...) |
+//! | 2      | 4    | src_eid, big-endian            |
+//! | 6      | 4    | body length, big-endian        |
+//! | 10     | n    | body                           |
+//!
+//! Swap messages carry `SwapParams` as their body, ABA swap messages follow it with the
+//! `ReturnParams` for the return leg. The return leg itself is a `SWAP_RESULT_TYPE` message
+//! carrying `SwapResult`, or a `SWAP_REFUND_TYPE` message carrying `SwapRefund` when the swap
+//! could not execute and the sender chose `REFUND_TO_SOURCE`.
+//!
+//! Bodies are encoded in field order with fixed-width big-endian integers, `[u8; 32]` as raw
+//! bytes and `Vec`s prefixed by their element count as a big-endian `u64`. The `SwapParams`
+//! body of version 2 is, with `n` hops in `path`:
+//!
+//! | size   | field                |
+//! |--------|----------------------|
+//! | 32     | token_in             |
+//! | 32     | token_out            |
+//! | 8      | amount_in            |
+//! | 8      | min_amount_out       |
+//! | 8      | path length `n`      |
+//! | 32 * n | path                 |
+//! | 1      | dex_choice           |
+//! | 8      | deadline             |
+//! | 32     | dex_address          |
+//! | 32     | recipient            |
+//! | 4      | fee                  |
+//! | 16     | sqrt_price_limit_x96 |
+//! | 1      | refund_policy        |
+//!
+//! Version 1 bodies end before `refund_policy` and are rejected.
+//!
+//! `refund_policy` covers swaps that can't execute because they expired or their DEX adapter is
+//! disabled or mismatched. A swap whose output falls below `min_amount_out` reverts instead, as
+//! slippage is only known once the adapter ran, and stays retryable in the endpoint.
+
+use anchor_lang::prelude::*;
+use bincode::{config, Decode, Encode};
+
+use crate::{dex, errors::CounterError};
+
+pub const VERSION: u8 = 2;
+
 pub const VANILLA_TYPE: u8 = 1;
 pub const COMPOSED_TYPE: u8 = 2;
-// ABA_TYPE & COMPOSED_ABA_TYPE are not supported
-// pub const ABA_TYPE: u8 = 3;
-// pub const COMPOSED_ABA_TYPE: u8 = 4;
+pub const ABA_TYPE: u8 = 3;
+pub const COMPOSED_ABA_TYPE: u8 = 4;
+// Return legs of an ABA swap, only ever sent
+pub const SWAP_RESULT_TYPE: u8 = 5;
+pub const SWAP_REFUND_TYPE: u8 = 6;
+
+// Where `token_in` goes when a swap can't execute. Refunds to the source chain travel on the
+// return leg, so they need an ABA message.
+pub const REFUND_TO_RECIPIENT: u8 = 0;
+pub const REFUND_TO_SOURCE: u8 = 1;
+
+pub const VERSION_OFFSET: usize = 0;
+pub const MSG_TYPE_OFFSET: usize = 1;
+pub const SRC_EID_OFFSET: usize = 2;
+pub const BODY_LEN_OFFSET: usize = 6;
+pub const BODY_OFFSET: usize = 10;
+
+// Upper bound on the encoded body, so a forged `path` length can never exhaust the heap
+pub const MAX_BODY_LEN: usize = 1024;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Encode, Decode)]
+pub struct SwapParams {
+    pub token_in: [u8; 32],
+    pub token_out: [u8; 32],
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    pub path: Vec<[u8; 32]>,
+    pub dex_choice: u8,
+    pub deadline: u64,
+    pub dex_address: [u8; 32],
+    pub recipient: [u8; 32],
+    pub fee: u32,
+    pub sqrt_price_limit_x96: u128,
+    pub refund_policy: u8, // REFUND_* applied if the swap can't execute
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Encode, Decode)]
+pub struct ReturnParams {
+    pub native_fee: u64,  // Most the executor pays for the return leg
+    pub options: Vec<u8>, // Options for the return leg
+}
+
+#[derive(Clone, Encode, Decode)]
+pub struct SwapResult {
+    pub guid: [u8; 32],
+    pub amount_out: u64,
+    pub recipient: [u8; 32],
+}
+
+#[derive(Clone, Encode, Decode)]
+pub struct SwapRefund {
+    pub guid: [u8; 32],
+    pub token_in: [u8; 32],
+    pub amount_in: u64,
+    pub recipient: [u8; 32],
+}
+
+fn body_config() -> impl config::Config {
+    config::standard()
+        .with_big_endian()
+        .with_fixed_int_encoding()
+        .with_limit::<MAX_BODY_LEN>()
+}
 
-pub const MSG_TYPE_OFFSET: usize = 0;
-pub const SRC_EID_OFFSET: usize = 1;
+pub fn is_composed(msg_type: u8) -> bool {
+    msg_type == COMPOSED_TYPE || msg_type == COMPOSED_ABA_TYPE
+}
+
+pub fn is_aba(msg_type: u8) -> bool {
+    msg_type == ABA_TYPE || msg_type == COMPOSED_ABA_TYPE
+}
 
-pub fn encode(msg_type: u8, src_eid: u32) -> Vec<u8> {
-    let mut encoded = Vec::new();
+/// Encodes a swap message, `return_params` must be given for ABA types and only for them.
+pub fn encode_swap(
+    msg_type: u8,
+    src_eid: u32,
+    swap_params: &SwapParams,
+    return_params: Option<&ReturnParams>,
+) -> Result<Vec<u8>> {
+    require!(
+        matches!(msg_type, VANILLA_TYPE | COMPOSED_TYPE | ABA_TYPE | COMPOSED_ABA_TYPE)
+            && is_aba(msg_type) == return_params.is_some(),
+        CounterError::InvalidMessageType
+    );
+    check_refund_policy(msg_type, swap_params.refund_policy)?;
+    require!(swap_params.path.len() <= dex::MAX_PATH_LEN, CounterError::PathTooLong);
+
+    let mut body = encode_body(swap_params)?;
+    if let Some(return_params) = return_params {
+        body.extend(encode_body(return_params)?);
+    }
+    // The receiving side refuses larger bodies, so never pay to send one
+    require!(body.len() <= MAX_BODY_LEN, CounterError::MessageTooLarge);
+    Ok(encode(msg_type, src_eid, body))
+}
+
+pub fn encode_swap_result(src_eid: u32, swap_result: &SwapResult) -> Result<Vec<u8>> {
+    Ok(encode(SWAP_RESULT_TYPE, src_eid, encode_body(swap_result)?))
+}
+
+pub fn encode_swap_refund(src_eid: u32, swap_refund: &SwapRefund) -> Result<Vec<u8>> {
+    Ok(encode(SWAP_REFUND_TYPE, src_eid, encode_body(swap_refund)?))
+}
+
+fn encode_body<T: Encode>(value: &T) -> Result<Vec<u8>> {
+    bincode::encode_to_vec(value, body_config())
+        .map_err(|_| CounterError::MessageEncodingFailed.into())
+}
+
+fn encode(msg_type: u8, src_eid: u32, body: Vec<u8>) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(BODY_OFFSET + body.len());
+    encoded.push(VERSION);
     encoded.push(msg_type);
     encoded.extend_from_slice(&src_eid.to_be_bytes());
+    encoded.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    encoded.extend_from_slice(&body);
     encoded
 }
 
-pub fn msg_type(message: &[u8]) -> u8 {
-    message[MSG_TYPE_OFFSET]
+pub fn decode_swap(message: &[u8]) -> Result<SwapParams> {
+    Ok(decode(message)?.0)
+}
+
+/// Decodes the return leg parameters of an ABA swap message.
+pub fn decode_return(message: &[u8]) -> Result<ReturnParams> {
+    decode(message)?.1.ok_or_else(|| CounterError::InvalidMessageType.into())
 }
 
-pub fn src_eid(message: &[u8]) -> u32 {
-    let mut eid_bytes = [0; 4];
-    eid_bytes.copy_from_slice(&message[SRC_EID_OFFSET..SRC_EID_OFFSET + 4]);
-    u32::from_be_bytes(eid_bytes)
+fn decode(message: &[u8]) -> Result<(SwapParams, Option<ReturnParams>)> {
+    require!(version(message)? == VERSION, CounterError::UnsupportedMessageVersion);
+    let msg_type = msg_type(message)?;
+
+    let body_len = body_len(message)? as usize;
+    let body = &message[BODY_OFFSET..];
+    require!(body_len <= MAX_BODY_LEN, CounterError::MessageDecodingFailed);
+    require!(body.len() >= body_len, CounterError::MessageTooShort);
+    require!(body.len() == body_len, CounterError::TrailingBytes);
+
+    let (swap_params, mut read) = decode_body::<SwapParams>(body)?;
+    check_refund_policy(msg_type, swap_params.refund_policy)?;
+    let return_params = if is_aba(msg_type) {
+        let (return_params, return_read) = decode_body::<ReturnParams>(&body[read..])?;
+        read += return_read;
+        Some(return_params)
+    } else {
+        None
+    };
+    require!(read == body_len, CounterError::TrailingBytes);
+    Ok((swap_params, return_params))
+}
+
+fn check_refund_policy(msg_type: u8, refund_policy: u8) -> Result<()> {
+    require!(
+        refund_policy == REFUND_TO_RECIPIENT
+            || (refund_policy == REFUND_TO_SOURCE && is_aba(msg_type)),
+        CounterError::InvalidRefundPolicy
+    );
+    Ok(())
+}
+
+fn decode_body<T: Decode>(body: &[u8]) -> Result<(T, usize)> {
+    bincode::decode_from_slice(body, body_config())
+        .map_err(|_| CounterError::MessageDecodingFailed.into())
+}
+
+pub fn version(message: &[u8]) -> Result<u8> {
+    read_u8(message, VERSION_OFFSET)
+}
+
+/// Reads the type of an inbound swap message, rejecting anything else.
+pub fn msg_type(message: &[u8]) -> Result<u8> {
+    match read_u8(message, MSG_TYPE_OFFSET)? {
+        msg_type @ (VANILLA_TYPE | COMPOSED_TYPE | ABA_TYPE | COMPOSED_ABA_TYPE) => Ok(msg_type),
+        _ => Err(CounterError::InvalidMessageType.into()),
+    }
+}
+
+pub fn src_eid(message: &[u8]) -> Result<u32> {
+    read_u32(message, SRC_EID_OFFSET)
+}
+
+pub fn body_len(message: &[u8]) -> Result<u32> {
+    read_u32(message, BODY_LEN_OFFSET)
+}
+
+fn read_u8(message: &[u8], offset: usize) -> Result<u8> {
+    message.get(offset).copied().ok_or_else(|| CounterError::MessageTooShort.into())
+}
+
+fn read_u32(message: &[u8], offset: usize) -> Result<u32> {
+    let bytes = message.get(offset..offset + 4).ok_or(CounterError::MessageTooShort)?;
+    let mut u32_bytes = [0; 4];
+    u32_bytes.copy_from_slice(bytes);
+    Ok(u32::from_be_bytes(u32_bytes))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    // Vanilla swap from eid 30101, header then `SwapParams` field by field
+    const VANILLA_SWAP: &str = concat!(
+        "02",
+        "01",
+        "00007595",
+        "000000b6",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "2222222222222222222222222222222222222222222222222222222222222222",
+        "00000000000f4240",
+        "00000000000f1b30",
+        "0000000000000000",
+        "01",
+        "000000006553f100",
+        "3333333333333333333333333333333333333333333333333333333333333333",
+        "4444444444444444444444444444444444444444444444444444444444444444",
+        "00000bb8",
+        "00000000000000000000000000000000",
+        "00",
+    );
+
+    // ABA swap over a three mint path refunding to the source, `ReturnParams` follow the swap
+    const ABA_SWAP: &str = concat!(
+        "02",
+        "03",
+        "00007595",
+        "00000128",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "2222222222222222222222222222222222222222222222222222222222222222",
+        "00000000000f4240",
+        "00000000000f1b30",
+        "0000000000000003",
+        "1111111111111111111111111111111111111111111111111111111111111111",
+        "5555555555555555555555555555555555555555555555555555555555555555",
+        "2222222222222222222222222222222222222222222222222222222222222222",
+        "01",
+        "000000006553f100",
+        "3333333333333333333333333333333333333333333333333333333333333333",
+        "4444444444444444444444444444444444444444444444444444444444444444",
+        "00000bb8",
+        "00000000000000000000000000000000",
+        "01",
+        "0000000000001388",
+        "0000000000000002",
+        "0003",
+    );
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Swap the tests across the crate start from, overriding the fields they care about.
+    pub(crate) fn swap_params() -> SwapParams {
+        SwapParams {
+            token_in: [0x11; 32],
+            token_out: [0x22; 32],
+            amount_in: 1_000_000,
+            min_amount_out: 990_000,
+            path: vec![],
+            dex_choice: 1,
+            deadline: 1_700_000_000,
+            dex_address: [0x33; 32],
+            recipient: [0x44; 32],
+            fee: 3_000,
+            sqrt_price_limit_x96: 0,
+            refund_policy: REFUND_TO_RECIPIENT,
+        }
+    }
+
+    fn aba_swap_params() -> (SwapParams, ReturnParams) {
+        let mut swap_params = swap_params();
+        swap_params.path = vec![[0x11; 32], [0x55; 32], [0x22; 32]];
+        swap_params.refund_policy = REFUND_TO_SOURCE;
+        (swap_params, ReturnParams { native_fee: 5_000, options: vec![0x00, 0x03] })
+    }
+
+    #[test]
+    fn encodes_the_vanilla_swap_vector() {
+        let message = encode_swap(VANILLA_TYPE, 30101, &swap_params(), None).unwrap();
+        assert_eq!(message, unhex(VANILLA_SWAP));
+    }
+
+    #[test]
+    fn decodes_the_vanilla_swap_vector() {
+        let message = unhex(VANILLA_SWAP);
+        assert_eq!(version(&message).unwrap(), VERSION);
+        assert_eq!(msg_type(&message).unwrap(), VANILLA_TYPE);
+        assert_eq!(src_eid(&message).unwrap(), 30101);
+        assert_eq!(body_len(&message).unwrap() as usize, message.len() - BODY_OFFSET);
+
+        let decoded = decode_swap(&message).unwrap();
+        let expected = swap_params();
+        assert_eq!(decoded.token_in, expected.token_in);
+        assert_eq!(decoded.token_out, expected.token_out);
+        assert_eq!(decoded.amount_in, expected.amount_in);
+        assert_eq!(decoded.min_amount_out, expected.min_amount_out);
+        assert!(decoded.path.is_empty());
+        assert_eq!(decoded.dex_choice, expected.dex_choice);
+        assert_eq!(decoded.deadline, expected.deadline);
+        assert_eq!(decoded.dex_address, expected.dex_address);
+        assert_eq!(decoded.recipient, expected.recipient);
+        assert_eq!(decoded.fee, expected.fee);
+        assert_eq!(decoded.sqrt_price_limit_x96, expected.sqrt_price_limit_x96);
+        assert_eq!(decoded.refund_policy, expected.refund_policy);
+        assert!(decode_return(&message).is_err());
+    }
+
+    #[test]
+    fn encodes_the_aba_swap_vector() {
+        let (swap_params, return_params) = aba_swap_params();
+        let message = encode_swap(ABA_TYPE, 30101, &swap_params, Some(&return_params)).unwrap();
+        assert_eq!(message, unhex(ABA_SWAP));
+    }
+
+    #[test]
+    fn decodes_the_aba_swap_vector() {
+        let message = unhex(ABA_SWAP);
+        assert_eq!(msg_type(&message).unwrap(), ABA_TYPE);
+
+        let decoded = decode_swap(&message).unwrap();
+        let (expected, expected_return) = aba_swap_params();
+        assert_eq!(decoded.path, expected.path);
+        assert_eq!(decoded.refund_policy, REFUND_TO_SOURCE);
+
+        let decoded_return = decode_return(&message).unwrap();
+        assert_eq!(decoded_return.native_fee, expected_return.native_fee);
+        assert_eq!(decoded_return.options, expected_return.options);
+    }
+
+    #[test]
+    fn rejects_the_vanilla_swap_vector_under_another_version() {
+        let mut message = unhex(VANILLA_SWAP);
+        message[VERSION_OFFSET] = 1;
+        assert_eq!(
+            decode_swap(&message).err(),
+            Some(CounterError::UnsupportedMessageVersion.into())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decoding_arbitrary_bytes_never_panics(message in vec(any::<u8>(), 0..2 * MAX_BODY_LEN)) {
+            let _ = version(&message);
+            let _ = msg_type(&message);
+            let _ = src_eid(&message);
+            let _ = body_len(&message);
+            let _ = decode(&message);
+            let _ = decode_swap(&message);
+            let _ = decode_return(&message);
+        }
+
+        #[test]
+        fn decoding_arbitrary_bodies_never_panics(
+            msg_type in VANILLA_TYPE..=COMPOSED_ABA_TYPE,
+            body in vec(any::<u8>(), 0..2 * MAX_BODY_LEN),
+        ) {
+            // A well-formed header gets arbitrary bytes past the header checks into the body
+            let message = encode(msg_type, 30101, body);
+            let _ = decode_swap(&message);
+            let _ = decode_return(&message);
+        }
+
+        #[test]
+        fn truncated_messages_never_decode(len in 0..unhex(ABA_SWAP).len()) {
+            let message = unhex(ABA_SWAP);
+            prop_assert!(decode_swap(&message[..len]).is_err());
+            prop_assert!(decode_return(&message[..len]).is_err());
+        }
+    }
 }
@@ -0,0 +1,705 @@
+//! Swap settlement shared by `lz_receive`, `lz_compose` and `retry_message`.
+//!
+//! `check` loads the swap accounts from the remaining accounts and validates a message against
+//! them without side effects, so a non-blocking remote can store any failure, down to a message
+//! that doesn't decode or an account that doesn't match it, and retry it later. `execute` then
+//! swaps or refunds, once it has moved tokens any error has to revert the whole transaction.
+
+use anchor_lang::error::Error;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use oapp::endpoint::{
+    instructions::SendParams as EndpointSendParams, state::EndpointSettings, ID as ENDPOINT_ID,
+};
+
+use crate::msg_codec::{ReturnParams, SwapParams};
+use crate::*;
+
+// Swap accounts leading the remaining accounts handed to `check`, in the order of `SwapAccounts`
+pub const SWAP_ACCOUNTS_LEN: usize = 11;
+
+/// A received message, as delivered to `lz_receive` or stored in a `FailedMessage`.
+pub struct InboundMessage<'m> {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub message: &'m [u8],
+}
+
+pub struct Settlement<'a, 'info> {
+    pub program_id: &'a Pubkey,
+    pub payer: &'a Signer<'info>,
+    pub count: &'a Account<'info, Count>,
+    pub remote: &'a mut Account<'info, Remote>,
+    pub receipt: &'a mut Account<'info, MessageReceipt>,
+    pub lz_receive_types_accounts: &'a Account<'info, LzReceiveTypesAccounts>,
+    pub endpoint: &'a Account<'info, EndpointSettings>,
+    pub associated_token_program: &'a Program<'info, AssociatedToken>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+/// The accounts a swap message names, checked against it by `check`.
+struct SwapAccounts<'info> {
+    token_in_mint: AccountInfo<'info>,
+    token_out_mint: AccountInfo<'info>,
+    token_in_vault: AccountInfo<'info>,
+    token_out_vault: AccountInfo<'info>,
+    recipient: AccountInfo<'info>,
+    recipient_token_account: AccountInfo<'info>, // Created on payout if missing
+    recipient_refund_account: AccountInfo<'info>, // Created on refund if missing
+    dex_adapter: DexAdapter,
+    dex_program: AccountInfo<'info>,
+    token_in_program: AccountInfo<'info>,
+    token_out_program: AccountInfo<'info>,
+    token_in_decimals: u8,
+    token_out_decimals: u8,
+}
+
+impl<'info> SwapAccounts<'info> {
+    /// Whichever of the two token programs owns `token_account`, intermediate vaults have to
+    /// live under one of them.
+    fn token_program_of(&self, token_account: &AccountInfo) -> Option<&AccountInfo<'info>> {
+        [&self.token_in_program, &self.token_out_program]
+            .into_iter()
+            .find(|token_program| token_account.owner == token_program.key)
+    }
+}
+
+/// What `check` resolved a message to, consumed by `execute`.
+pub struct Plan<'r, 'info> {
+    swap_params: SwapParams,
+    return_params: Option<ReturnParams>,
+    route: Vec<Pubkey>,
+    failure: Option<CounterError>,
+    accounts: Box<SwapAccounts<'info>>,
+    intermediate_vaults: &'r [AccountInfo<'info>],
+    accounts_for_return: &'r [AccountInfo<'info>],
+    token_in_hook_accounts: &'r [AccountInfo<'info>],
+    token_out_hook_accounts: &'r [AccountInfo<'info>],
+    adapter_accounts: &'r [AccountInfo<'info>],
+}
+
+impl<'a, 'info> Settlement<'a, 'info> {
+    /// Checks and executes `message`. Non-blocking remotes keep a message failing its checks in
+    /// `failed_message` for `retry_message` instead of reverting, so one bad message can't hold
+    /// up the channel.
+    pub fn settle(
+        &mut self,
+        message: &InboundMessage,
+        remaining_accounts: &[AccountInfo<'info>],
+        failed_message: &AccountInfo<'info>,
+        failed_message_bump: u8,
+        seeds: &[&[u8]],
+    ) -> Result<()> {
+        match self.check(message, remaining_accounts) {
+            Ok(plan) => self.execute(message, plan, seeds),
+            Err(err) if self.remote.non_blocking => {
+                msg!("Message failed: {}", err);
+                self.store_failed(message, error_code(&err), failed_message, failed_message_bump)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Validates `message` against the accounts. `remaining_accounts` starts with the
+    /// `SWAP_ACCOUNTS_LEN` swap accounts, then the vaults for intermediate hops, the return leg
+    /// accounts of ABA swaps and the transfer hook accounts of `token_in` and `token_out`, any
+    /// accounts after them belong to the adapter.
+    pub fn check<'r>(
+        &self,
+        message: &InboundMessage,
+        remaining_accounts: &'r [AccountInfo<'info>],
+    ) -> Result<Plan<'r, 'info>> {
+        // Decode SwapParams from message
+        let swap_params = msg_codec::decode_swap(message.message)?;
+        require!(
+            msg_codec::src_eid(message.message)? == message.src_eid,
+            CounterError::InvalidSourceEid
+        );
+
+        // Verify sender
+        require!(message.sender == self.remote.address, CounterError::UnauthorizedSender);
+
+        let route = dex::route(&swap_params)?;
+        let accounts = self.load_swap_accounts(&swap_params, remaining_accounts)?;
+        let remaining_accounts = &remaining_accounts[SWAP_ACCOUNTS_LEN..];
+
+        // Swaps and refunds both pay `amount_in` out of the vault. Short of it, `execute` would
+        // revert inside the transfer, where a non-blocking remote can no longer store the message.
+        require!(
+            dex::balance(&accounts.token_in_vault)? >= swap_params.amount_in,
+            CounterError::InsufficientVaultBalance
+        );
+
+        // ABA swaps answer on the same channel, so it has to be open both ways
+        let msg_type = msg_codec::msg_type(message.message)?;
+        let return_params = if msg_codec::is_aba(msg_type) {
+            require!(self.remote.outbound_enabled, CounterError::RemoteDisabled);
+            Some(msg_codec::decode_return(message.message)?)
+        } else {
+            None
+        };
+
+        let hops = route.len() - 1;
+        let intermediate_vaults = remaining_accounts
+            .get(..hops - 1)
+            .ok_or(CounterError::InvalidSwapPath)?;
+        for (mint, vault) in route[1..hops].iter().zip(intermediate_vaults) {
+            let token_program =
+                accounts.token_program_of(vault).ok_or(CounterError::InvalidSwapPath)?;
+            require_keys_eq!(
+                vault.key(),
+                get_associated_token_address_with_program_id(
+                    &self.count.key(),
+                    mint,
+                    token_program.key,
+                ),
+                CounterError::InvalidSwapPath
+            );
+        }
+
+        let return_accounts_len = if return_params.is_some() {
+            self.lz_receive_types_accounts
+                .return_accounts(message.src_eid)
+                .ok_or(CounterError::ReturnAccountsNotSet)?
+                .len()
+        } else {
+            0
+        };
+        let accounts_for_return = remaining_accounts
+            .get(hops - 1..hops - 1 + return_accounts_len)
+            .ok_or(CounterError::ReturnAccountsNotSet)?;
+        let remaining_accounts = &remaining_accounts[hops - 1 + return_accounts_len..];
+
+        // The hooks check their accounts themselves, only their number is needed here
+        let types = &self.lz_receive_types_accounts;
+        let token_in_hooks_len = types.transfer_hook_accounts(accounts.token_in_mint.key).len();
+        let token_out_hooks_len = types.transfer_hook_accounts(accounts.token_out_mint.key).len();
+        let token_in_hook_accounts = remaining_accounts
+            .get(..token_in_hooks_len)
+            .ok_or(CounterError::InvalidSwapAccount)?;
+        let token_out_hook_accounts = remaining_accounts
+            .get(token_in_hooks_len..token_in_hooks_len + token_out_hooks_len)
+            .ok_or(CounterError::InvalidSwapAccount)?;
+        let adapter_accounts = &remaining_accounts[token_in_hooks_len + token_out_hooks_len..];
+
+        let now = Clock::get()?.unix_timestamp;
+        let failure = swap_failure(&swap_params, &accounts.dex_adapter, now);
+        Ok(Plan {
+            swap_params,
+            return_params,
+            route,
+            failure,
+            accounts,
+            intermediate_vaults,
+            accounts_for_return,
+            token_in_hook_accounts,
+            token_out_hook_accounts,
+            adapter_accounts,
+        })
+    }
+
+    /// Loads the swap accounts leading `remaining_accounts`, checking they are the ones
+    /// `swap_params` describes.
+    fn load_swap_accounts(
+        &self,
+        swap_params: &SwapParams,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<Box<SwapAccounts<'info>>> {
+        let accounts = remaining_accounts
+            .get(..SWAP_ACCOUNTS_LEN)
+            .ok_or(CounterError::InvalidSwapAccount)?;
+        let [
+            token_in_mint,
+            token_out_mint,
+            token_in_vault,
+            token_out_vault,
+            recipient,
+            recipient_token_account,
+            recipient_refund_account,
+            dex_adapter,
+            dex_program,
+            token_in_program,
+            token_out_program,
+        ] = accounts
+        else {
+            return err!(CounterError::InvalidSwapAccount);
+        };
+
+        // Each end of a route is owned by its own token program, SPL Token or Token-2022
+        for token_program in [token_in_program, token_out_program] {
+            require!(
+                token_program.key() == anchor_spl::token::ID
+                    || token_program.key() == anchor_spl::token_2022::ID,
+                CounterError::InvalidSwapAccount
+            );
+        }
+        require!(
+            token_in_mint.key().to_bytes() == swap_params.token_in
+                && token_out_mint.key().to_bytes() == swap_params.token_out,
+            CounterError::SwapExecutionFailed
+        );
+        let token_in_decimals = dex::mint_decimals(token_in_mint, token_in_program.key)?;
+        let token_out_decimals = dex::mint_decimals(token_out_mint, token_out_program.key)?;
+
+        // Vaults and the recipient's accounts are the associated token accounts of their mints
+        let count = self.count.key();
+        require!(
+            recipient.key().to_bytes() == swap_params.recipient,
+            CounterError::UnauthorizedRecipient
+        );
+        for (account, owner, mint, token_program) in [
+            (token_in_vault, &count, token_in_mint, token_in_program),
+            (token_out_vault, &count, token_out_mint, token_out_program),
+            (recipient_token_account, recipient.key, token_out_mint, token_out_program),
+            (recipient_refund_account, recipient.key, token_in_mint, token_in_program),
+        ] {
+            require!(
+                account.is_writable
+                    && account.key()
+                        == get_associated_token_address_with_program_id(
+                            owner,
+                            mint.key,
+                            token_program.key,
+                        ),
+                CounterError::InvalidSwapAccount
+            );
+        }
+
+        // Only route through the adapter registered for this store and dex choice
+        require!(dex_adapter.owner == self.program_id, CounterError::UnknownDexChoice);
+        let dex_adapter_data =
+            DexAdapter::try_deserialize(&mut &dex_adapter.try_borrow_data()?[..])
+                .map_err(|_| CounterError::UnknownDexChoice)?;
+        let dex_adapter_key = Pubkey::create_program_address(
+            &[
+                DEX_ADAPTER_SEED,
+                &count.to_bytes(),
+                &swap_params.dex_choice.to_be_bytes(),
+                &[dex_adapter_data.bump],
+            ],
+            self.program_id,
+        )
+        .map_err(|_| CounterError::UnknownDexChoice)?;
+        require!(
+            dex_adapter.key() == dex_adapter_key
+                && dex_adapter_data.dex_choice == swap_params.dex_choice,
+            CounterError::UnknownDexChoice
+        );
+        // The executor passes the program the message names, whether the registry still agrees
+        // is checked in `swap_failure`
+        require!(
+            dex_program.key().to_bytes() == swap_params.dex_address,
+            CounterError::DexAddressMismatch
+        );
+        require!(dex_program.executable, CounterError::InvalidSwapAccount);
+
+        Ok(Box::new(SwapAccounts {
+            token_in_mint: token_in_mint.clone(),
+            token_out_mint: token_out_mint.clone(),
+            token_in_vault: token_in_vault.clone(),
+            token_out_vault: token_out_vault.clone(),
+            recipient: recipient.clone(),
+            recipient_token_account: recipient_token_account.clone(),
+            recipient_refund_account: recipient_refund_account.clone(),
+            dex_adapter: dex_adapter_data,
+            dex_program: dex_program.clone(),
+            token_in_program: token_in_program.clone(),
+            token_out_program: token_out_program.clone(),
+            token_in_decimals,
+            token_out_decimals,
+        }))
+    }
+
+    /// Swaps, or refunds the input if the swap can't execute, records the outcome on the
+    /// receipt and reports it back to the source chain for ABA swaps.
+    pub fn execute(
+        &mut self,
+        message: &InboundMessage,
+        plan: Plan<'_, 'info>,
+        seeds: &[&[u8]],
+    ) -> Result<()> {
+        let swap_params = &plan.swap_params;
+        let accounts = &plan.accounts;
+        let (outcome, amount_out) = match plan.failure {
+            None => {
+                let amount_out = self.execute_swap(&plan, seeds)?;
+
+                self.remote.swaps_executed += 1;
+                self.remote.volume_in =
+                    self.remote.volume_in.saturating_add(swap_params.amount_in as u128);
+
+                emit!(SwapExecuted {
+                    count: self.count.key(),
+                    guid: message.guid,
+                    token_in: accounts.token_in_mint.key(),
+                    token_out: accounts.token_out_mint.key(),
+                    amount_in: swap_params.amount_in,
+                    amount_out,
+                    recipient: accounts.recipient.key(),
+                });
+                (OUTCOME_EXECUTED, amount_out)
+            }
+            Some(reason) => {
+                // Refunds to the source chain are paid out there, the input stays in the vault
+                let outcome = if swap_params.refund_policy == msg_codec::REFUND_TO_SOURCE {
+                    OUTCOME_REFUNDED_TO_SOURCE
+                } else {
+                    self.refund_to_recipient(
+                        accounts,
+                        plan.token_in_hook_accounts,
+                        swap_params.amount_in,
+                        seeds,
+                    )?;
+                    OUTCOME_REFUNDED_TO_RECIPIENT
+                };
+                self.remote.swaps_failed += 1;
+
+                emit!(SwapRefunded {
+                    count: self.count.key(),
+                    guid: message.guid,
+                    token_in: accounts.token_in_mint.key(),
+                    amount_in: swap_params.amount_in,
+                    recipient: accounts.recipient.key(),
+                    refund_policy: swap_params.refund_policy,
+                    reason: reason.into(),
+                });
+                (outcome, 0)
+            }
+        };
+
+        self.receipt.token_in = accounts.token_in_mint.key();
+        self.receipt.token_out = accounts.token_out_mint.key();
+        self.receipt.amount_in = swap_params.amount_in;
+        self.receipt.amount_out = amount_out;
+        self.receipt.recipient = accounts.recipient.key();
+        self.receipt.outcome = outcome;
+
+        // Report the outcome back to the source chain, the executor pays up to the fee the
+        // sender funded
+        if let Some(return_params) = plan.return_params {
+            let (return_type, return_message) = if outcome == OUTCOME_REFUNDED_TO_SOURCE {
+                let return_message = msg_codec::encode_swap_refund(
+                    self.endpoint.eid,
+                    &msg_codec::SwapRefund {
+                        guid: message.guid,
+                        token_in: swap_params.token_in,
+                        amount_in: swap_params.amount_in,
+                        recipient: swap_params.recipient,
+                    },
+                )?;
+                (msg_codec::SWAP_REFUND_TYPE, return_message)
+            } else {
+                let return_message = msg_codec::encode_swap_result(
+                    self.endpoint.eid,
+                    &msg_codec::SwapResult {
+                        guid: message.guid,
+                        amount_out,
+                        recipient: swap_params.recipient,
+                    },
+                )?;
+                (msg_codec::SWAP_RESULT_TYPE, return_message)
+            };
+            let messaging_receipt = oapp::endpoint_cpi::send(
+                ENDPOINT_ID,
+                self.count.key(),
+                plan.accounts_for_return,
+                seeds,
+                EndpointSendParams {
+                    dst_eid: message.src_eid,
+                    receiver: self.remote.address,
+                    message: return_message,
+                    options: return_params.options,
+                    native_fee: return_params.native_fee,
+                    lz_token_fee: 0,
+                },
+            )?;
+            emit!(MessageSent {
+                count: self.count.key(),
+                dst_eid: message.src_eid,
+                guid: messaging_receipt.guid,
+                nonce: messaging_receipt.nonce,
+                msg_type: return_type,
+                native_fee: messaging_receipt.fee.native_fee,
+                lz_token_fee: messaging_receipt.fee.lz_token_fee,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Walks the route through the DEX adapter, each hop swapping everything the previous one
+    /// produced, and pays the output to the recipient. Returns what the recipient received.
+    fn execute_swap(&self, plan: &Plan<'_, 'info>, seeds: &[&[u8]]) -> Result<u64> {
+        let swap_params = &plan.swap_params;
+        let accounts = &plan.accounts;
+        let hops = plan.route.len() - 1;
+        let mut vaults = vec![accounts.token_in_vault.clone()];
+        vaults.extend_from_slice(plan.intermediate_vaults);
+        vaults.push(accounts.token_out_vault.clone());
+        let mut token_programs = vec![&accounts.token_in_program];
+        for vault in plan.intermediate_vaults {
+            token_programs
+                .push(accounts.token_program_of(vault).ok_or(CounterError::InvalidSwapPath)?);
+        }
+        token_programs.push(&accounts.token_out_program);
+
+        let mut amount_out = swap_params.amount_in;
+        for hop in 0..hops {
+            let is_last_hop = hop == hops - 1;
+            let balance_before = dex::balance(&vaults[hop + 1])?;
+            dex::swap(
+                dex::SwapAccounts {
+                    dex_program: &accounts.dex_program,
+                    authority: &self.count.to_account_info(),
+                    source: &vaults[hop],
+                    destination: &vaults[hop + 1],
+                    source_token_program: token_programs[hop],
+                    destination_token_program: token_programs[hop + 1],
+                    // All hops' pools, the adapter finds this hop's by its mints
+                    adapter_accounts: plan.adapter_accounts,
+                },
+                amount_out,
+                if is_last_hop { swap_params.min_amount_out } else { 0 },
+                seeds,
+            )?;
+            amount_out = dex::balance(&vaults[hop + 1])?
+                .checked_sub(balance_before)
+                .ok_or(CounterError::SwapExecutionFailed)?;
+            require!(amount_out > 0, CounterError::SwapExecutionFailed);
+        }
+
+        // Transfer-fee mints withhold part of the payout, so the recipient gets less than the
+        // vault received. Don't trust the adapter to honour `min_amount_out`, check what the
+        // recipient will actually hold.
+        let vault_amount_out = amount_out;
+        let fee = dex::transfer_fee(
+            &accounts.token_out_mint,
+            vault_amount_out,
+            Clock::get()?.epoch,
+        )?;
+        amount_out = amount_received(vault_amount_out, fee, swap_params.min_amount_out)?;
+
+        // Hand the output to the recipient
+        self.create_recipient_account(
+            accounts,
+            &accounts.recipient_token_account,
+            &accounts.token_out_mint,
+            &accounts.token_out_program,
+        )?;
+        dex::transfer_checked(
+            dex::TransferAccounts {
+                token_program: &accounts.token_out_program,
+                from: &accounts.token_out_vault,
+                mint: &accounts.token_out_mint,
+                to: &accounts.recipient_token_account,
+                authority: &self.count.to_account_info(),
+                hook_accounts: plan.token_out_hook_accounts,
+            },
+            vault_amount_out,
+            accounts.token_out_decimals,
+            &[seeds],
+        )?;
+        Ok(amount_out)
+    }
+
+    /// Stores a message whose checks failed, for `retry_message` or `discard_message`.
+    fn store_failed(
+        &mut self,
+        message: &InboundMessage,
+        reason: u32,
+        account: &AccountInfo<'info>,
+        bump: u8,
+    ) -> Result<()> {
+        let count = self.count.key();
+        let failed_message = FailedMessage {
+            count,
+            guid: message.guid,
+            src_eid: message.src_eid,
+            sender: message.sender,
+            nonce: message.nonce,
+            message: message.message.to_vec(),
+            reason,
+            payer: self.payer.key(),
+            bump,
+        };
+        failed_message.create(
+            account,
+            self.payer,
+            self.system_program,
+            &[FAILED_MESSAGE_SEED, &count.to_bytes(), &message.guid, &[bump]],
+        )?;
+        self.receipt.outcome = OUTCOME_FAILED;
+        self.remote.swaps_failed += 1;
+
+        emit!(MessageFailed {
+            count,
+            guid: message.guid,
+            src_eid: message.src_eid,
+            nonce: message.nonce,
+            reason,
+        });
+        Ok(())
+    }
+
+    /// Returns `amount` of `token_in` to the recipient, creating their account if needed.
+    fn refund_to_recipient(
+        &self,
+        accounts: &SwapAccounts<'info>,
+        hook_accounts: &[AccountInfo<'info>],
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> Result<()> {
+        self.create_recipient_account(
+            accounts,
+            &accounts.recipient_refund_account,
+            &accounts.token_in_mint,
+            &accounts.token_in_program,
+        )?;
+        dex::transfer_checked(
+            dex::TransferAccounts {
+                token_program: &accounts.token_in_program,
+                from: &accounts.token_in_vault,
+                mint: &accounts.token_in_mint,
+                to: &accounts.recipient_refund_account,
+                authority: &self.count.to_account_info(),
+                hook_accounts,
+            },
+            amount,
+            accounts.token_in_decimals,
+            &[seeds],
+        )
+    }
+
+    /// Creates the recipient's associated token account for `mint`, paid by the payer, unless
+    /// it already exists.
+    fn create_recipient_account(
+        &self,
+        accounts: &SwapAccounts<'info>,
+        associated_token: &AccountInfo<'info>,
+        mint: &AccountInfo<'info>,
+        token_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        associated_token::create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: self.payer.to_account_info(),
+                associated_token: associated_token.clone(),
+                authority: accounts.recipient.clone(),
+                mint: mint.clone(),
+                system_program: self.system_program.to_account_info(),
+                token_program: token_program.clone(),
+            },
+        ))
+    }
+}
+
+/// Returns why the swap can't execute at unix time `now`, for failures that are down to the
+/// message rather than the executor. Slippage is only known once the adapter ran and a failing
+/// CPI reverts the whole transaction, so it can't be refunded and still reverts.
+fn swap_failure(
+    swap_params: &SwapParams,
+    dex_adapter: &DexAdapter,
+    now: i64,
+) -> Option<CounterError> {
+    if is_expired(swap_params.deadline, now) {
+        return Some(CounterError::SwapExpired);
+    }
+    if !dex_adapter.enabled {
+        return Some(CounterError::DexDisabled);
+    }
+    if dex_adapter.program_id.to_bytes() != swap_params.dex_address {
+        return Some(CounterError::DexAddressMismatch);
+    }
+    None
+}
+
+/// What the recipient receives of `vault_amount_out` once the mint withheld `fee`, as long as
+/// it still meets `min_amount_out`.
+fn amount_received(vault_amount_out: u64, fee: u64, min_amount_out: u64) -> Result<u64> {
+    let amount_out = vault_amount_out.checked_sub(fee).ok_or(CounterError::TransferFeeFailed)?;
+    require!(amount_out >= min_amount_out, CounterError::SlippageExceeded);
+    Ok(amount_out)
+}
+
+/// Whether a swap with `deadline` may no longer execute at `now`. The deadline itself is still
+/// in time.
+fn is_expired(deadline: u64, now: i64) -> bool {
+    now as u64 > deadline
+}
+
+/// Error code of `err`, as stored in `FailedMessage::reason`.
+pub fn error_code(err: &Error) -> u32 {
+    match err {
+        Error::AnchorError(err) => err.error_code_number,
+        Error::ProgramError(err) => u64::from(err.program_error.clone()) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::tests::{token_2022_mint, TRANSFER_FEE_BASIS_POINTS};
+    use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+
+    const MIN_AMOUNT_OUT: u64 = 990;
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn executes_swaps_whose_deadline_is_now() {
+        assert!(!is_expired(NOW as u64, NOW));
+    }
+
+    #[test]
+    fn executes_swaps_whose_deadline_is_a_second_away() {
+        assert!(!is_expired(NOW as u64 + 1, NOW));
+    }
+
+    #[test]
+    fn expires_swaps_whose_deadline_passed_a_second_ago() {
+        assert!(is_expired(NOW as u64 - 1, NOW));
+    }
+
+    #[test]
+    fn pays_out_an_output_of_exactly_min_amount_out() {
+        assert_eq!(amount_received(MIN_AMOUNT_OUT, 0, MIN_AMOUNT_OUT).unwrap(), MIN_AMOUNT_OUT);
+    }
+
+    #[test]
+    fn rejects_an_output_one_below_min_amount_out() {
+        assert_eq!(
+            amount_received(MIN_AMOUNT_OUT - 1, 0, MIN_AMOUNT_OUT).unwrap_err(),
+            CounterError::SlippageExceeded.into()
+        );
+    }
+
+    #[test]
+    fn rejects_a_transfer_fee_pushing_the_output_below_min_amount_out() {
+        let key = Pubkey::new_unique();
+        let owner = anchor_spl::token_2022::ID;
+        let mut lamports = 0;
+        let mut data = token_2022_mint(&[ExtensionType::TransferFeeConfig]);
+        let mint =
+            AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+
+        // The vault receives exactly the minimum, the recipient gets it less the fee
+        let fee = dex::transfer_fee(&mint, MIN_AMOUNT_OUT, 0).unwrap();
+        assert_eq!(TRANSFER_FEE_BASIS_POINTS, 100);
+        assert_eq!(fee, 10); // 1% of 990, rounded up
+        assert_eq!(
+            amount_received(MIN_AMOUNT_OUT, fee, MIN_AMOUNT_OUT).unwrap_err(),
+            CounterError::SlippageExceeded.into()
+        );
+
+        // Enough output to cover the fee still meets it
+        let vault_amount_out = 1_000;
+        let fee = dex::transfer_fee(&mint, vault_amount_out, 0).unwrap();
+        assert_eq!(
+            amount_received(vault_amount_out, fee, MIN_AMOUNT_OUT).unwrap(),
+            MIN_AMOUNT_OUT
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::*;
+
+#[account]
+pub struct DexAdapter {
+    pub dex_choice: u8,      // `SwapParams::dex_choice` this entry serves
+    pub program_id: Pubkey,  // Adapter program swaps are routed through
+    pub kind: u8,            // Interface the adapter program implements
+    pub enabled: bool,       // Disabled adapters reject every swap
+    pub bump: u8,            // PDA bump seed
+}
+
+impl DexAdapter {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+}
@@ -0,0 +1,74 @@
+use crate::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+
+#[account]
+pub struct FailedMessage {
+    pub count: Pubkey,
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub message: Vec<u8>, // Raw payload, re-executed by `retry_message`
+    pub reason: u32,      // Error code of the check that failed
+    pub payer: Pubkey,    // Paid the rent, refunded on retry or discard
+    pub bump: u8,
+}
+
+impl FailedMessage {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    /// Creates the PDA at `account` holding `self`, with rent from `payer`. Lamports sent to
+    /// the address beforehand are kept, so they can't stop a failure from being stored.
+    pub fn create<'info>(
+        &self,
+        account: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+    ) -> Result<()> {
+        let space = Self::SIZE + self.message.len();
+        let rent = Rent::get()?.minimum_balance(space);
+        if account.lamports() == 0 {
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    CreateAccount { from: payer.clone(), to: account.clone() },
+                    &[signer_seeds],
+                ),
+                rent,
+                space as u64,
+                &crate::ID,
+            )?;
+        } else {
+            let top_up = rent.saturating_sub(account.lamports());
+            if top_up > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        system_program.clone(),
+                        Transfer { from: payer.clone(), to: account.clone() },
+                    ),
+                    top_up,
+                )?;
+            }
+            system_program::allocate(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Allocate { account_to_allocate: account.clone() },
+                    &[signer_seeds],
+                ),
+                space as u64,
+            )?;
+            system_program::assign(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    Assign { account_to_assign: account.clone() },
+                    &[signer_seeds],
+                ),
+                &crate::ID,
+            )?;
+        }
+
+        let mut data = account.try_borrow_mut_data()?;
+        self.try_serialize(&mut &mut data[..])
+    }
+}
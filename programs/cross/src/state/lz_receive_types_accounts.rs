@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use oapp::endpoint_cpi::LzAccount;
+
+/// What `lz_receive_types` and `lz_compose_types` resolve swap accounts from. Each of them is
+/// only handed its own types PDA, so both hold a copy and every instruction setting one of the
+/// fields writes both.
+#[account]
+pub struct LzReceiveTypesAccounts {
+    pub count: Pubkey,                        // Count PDA `lz_receive` accounts are resolved for
+    pub return_accounts: Vec<ReturnAccounts>, // Send accounts for ABA return legs, per eid
+    pub token_2022_mints: Vec<Pubkey>,        // Vault mints owned by Token-2022
+    // Extra accounts of vault mints with a transfer hook, per mint
+    pub transfer_hook_accounts: Vec<TransferHookAccounts>,
+}
+
+impl LzReceiveTypesAccounts {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    pub fn return_accounts(&self, eid: u32) -> Option<&Vec<LzAccount>> {
+        self.return_accounts.iter().find(|r| r.eid == eid).map(|r| &r.accounts)
+    }
+
+    /// Extra accounts the transfer hook of `mint` needs, empty for mints without one.
+    pub fn transfer_hook_accounts(&self, mint: &Pubkey) -> &[LzAccount] {
+        self.transfer_hook_accounts
+            .iter()
+            .find(|h| &h.mint == mint)
+            .map_or(&[], |h| &h.accounts[..])
+    }
+
+    /// Token program owning `mint`, as recorded when its vault was created.
+    pub fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        if self.token_2022_mints.contains(mint) {
+            anchor_spl::token_2022::ID
+        } else {
+            anchor_spl::token::ID
+        }
+    }
+
+    /// Grows `account` to fit its current data, topping up rent from `payer`.
+    pub fn grow_to_fit<'info>(
+        account: &Account<'info, Self>,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<()> {
+        let space = 8 + account.try_to_vec()?.len();
+        let info = account.to_account_info();
+        if space <= info.data_len() {
+            return Ok(());
+        }
+
+        let rent = Rent::get()?.minimum_balance(space);
+        if rent > info.lamports() {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer { from: payer.clone(), to: info.clone() },
+                ),
+                rent - info.lamports(),
+            )?;
+        }
+        info.realloc(space, false)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct ReturnAccounts {
+    pub eid: u32,
+    pub accounts: Vec<LzAccount>, // Endpoint `send` accounts, payer as a default pubkey signer
+}
+
+#[derive(Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct TransferHookAccounts {
+    pub mint: Pubkey,
+    pub accounts: Vec<LzAccount>, // Validation account, hook program, then its extra accounts
+}
@@ -0,0 +1,32 @@
+use crate::*;
+
+pub const OUTCOME_EXECUTED: u8 = 1;
+pub const OUTCOME_REFUNDED_TO_RECIPIENT: u8 = 2;
+pub const OUTCOME_REFUNDED_TO_SOURCE: u8 = 3;
+// Stored as a `FailedMessage`, until it is retried or discarded
+pub const OUTCOME_FAILED: u8 = 4;
+pub const OUTCOME_DISCARDED: u8 = 5;
+// Queued for `lz_compose`, which settles composed swaps
+pub const OUTCOME_COMPOSED: u8 = 6;
+
+#[account]
+pub struct MessageReceipt {
+    pub guid: [u8; 32],
+    pub src_eid: u32,
+    pub sender: [u8; 32],
+    pub nonce: u64,
+    pub msg_type: u8,
+    pub token_in: Pubkey,
+    pub token_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub recipient: Pubkey,
+    pub outcome: u8,   // OUTCOME_* the message ended with
+    pub slot: u64,     // Slot the message was received in
+    pub payer: Pubkey, // Executor that funded the receipt, refunded on close
+    pub bump: u8,
+}
+
+impl MessageReceipt {
+    pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+}
@@ -1,5 +1,13 @@
 pub mod count;
+mod dex_adapter;
+mod failed_message;
+mod lz_receive_types_accounts;
+mod message_receipt;
 mod remote;
 
 pub use count::*;
+pub use dex_adapter::*;
+pub use failed_message::*;
+pub use lz_receive_types_accounts::*;
+pub use message_receipt::*;
 pub use remote::*;
@@ -4,8 +4,76 @@ use crate::*;
 pub struct Remote {
     pub address: [u8; 32],
     pub bump: u8,
+    pub inbound_enabled: bool,   // Accept messages from this peer
+    pub outbound_enabled: bool,  // Quote and send to this peer
+    pub messages_received: u64,  // Messages received from this peer
+    pub composed_sent: u64,      // Compose messages queued for them
+    pub swaps_executed: u64,     // Swaps executed for them
+    pub swaps_failed: u64,       // Swaps that could not execute
+    pub volume_in: u128,         // Sum of `amount_in` over executed swaps
+    pub last_nonce: u64,         // Nonce of the last received message
+    pub last_received_slot: u64, // Slot the last message was received in
+    pub ordered: bool,           // Only accept the nonce after `inbound_nonce`
+    pub inbound_nonce: u64,      // Last nonce processed in ordered mode
+    pub non_blocking: bool,      // Store failed messages for a retry instead of reverting
 }
 
 impl Remote {
     pub const SIZE: usize = 8 + std::mem::size_of::<Self>();
+
+    /// Moves an ordered remote past `nonce`, which has to be the one after `inbound_nonce`.
+    /// Delivery and the admin's skip, clear and burn all go through here and reject any other
+    /// nonce with `InvalidNonce`, so none of them can move the expected nonce back or past one
+    /// the endpoint still holds. Unordered remotes take any nonce.
+    pub fn consume_nonce(&mut self, nonce: u64) -> Result<()> {
+        if self.ordered {
+            require!(nonce == self.inbound_nonce + 1, CounterError::InvalidNonce);
+            self.inbound_nonce = nonce;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(ordered: bool, inbound_nonce: u64) -> Remote {
+        Remote {
+            address: [1; 32],
+            bump: 255,
+            inbound_enabled: true,
+            outbound_enabled: true,
+            messages_received: 0,
+            composed_sent: 0,
+            swaps_executed: 0,
+            swaps_failed: 0,
+            volume_in: 0,
+            last_nonce: 0,
+            last_received_slot: 0,
+            ordered,
+            inbound_nonce,
+            non_blocking: false,
+        }
+    }
+
+    #[test]
+    fn ordered_remotes_only_move_past_the_next_nonce() {
+        let mut remote = remote(true, 5);
+        for nonce in [0, 4, 5, 7, u64::MAX] {
+            let err = remote.consume_nonce(nonce).unwrap_err();
+            assert_eq!(err, CounterError::InvalidNonce.into());
+            assert_eq!(remote.inbound_nonce, 5);
+        }
+        remote.consume_nonce(6).unwrap();
+        assert_eq!(remote.inbound_nonce, 6);
+    }
+
+    #[test]
+    fn unordered_remotes_take_any_nonce() {
+        let mut remote = remote(false, 5);
+        remote.consume_nonce(2).unwrap();
+        remote.consume_nonce(9).unwrap();
+        assert_eq!(remote.inbound_nonce, 5);
+    }
 }